@@ -7,16 +7,17 @@
 
 SOURCE_DIR="$( cd "$( dirname "${BASH_SOURCE[0]}" )" >/dev/null 2>&1 && pwd )"
 
-main() {
+# Add the row of an external verifier speaking the line protocol to results.md
+external() {
+    (cd "$SOURCE_DIR" && cargo run --features libraries -- external "$@")
+}
 
 cd "$SOURCE_DIR"
-# Dalek, Zebra, BoringSSL, libra-crypto
-cargo test -- --nocapture --test-threads 1
+# Dalek, Zebra, BoringSSL, Hacl*, libra-crypto
+cargo run --features libraries -- results
 
 # CryptoKit
-pushd "$SOURCE_DIR/scripts/ed25519-ios"
-swift Ed25519.swift
-popd
+external CryptoKit swift scripts/ed25519-ios/Ed25519Protocol.swift
 
 # ed25519-java, BouncyCastle
 if [[ -z "$JAVA_HOME" ]]
@@ -34,28 +35,28 @@ CLASSPATH=\
 "$JAVA_LIBS"/com/google/code/gson/gson/2.8.6/gson-2.8.6.jar
 
 javac -cp $CLASSPATH src/main/java/*.java
-java -cp $CLASSPATH:src/main/java TestVectorChecker
 popd
+for library in ed25519-java BouncyCastle
+do
+    external $library java -cp "$CLASSPATH:$SOURCE_DIR/scripts/ed25519-java/src/main/java" ProtocolChecker $library
+done
 fi
 
 # ed25519-donna
 pushd "$SOURCE_DIR/scripts/ed25519-signal-donna/build"
 cmake ..
-make
-./test-donna
+make protocol-donna
 popd
+external ed25519-donna scripts/ed25519-signal-donna/build/protocol-donna
 
 # Go
-go run scripts/go/main.go
+external Go go run scripts/go/eddsa_protocol.go
 
 # npm
-NODE=$(which node)
-pushd "$SOURCE_DIR/scripts/npm"
-$NODE eddsa_test.js
-popd
+external npm node scripts/npm/eddsa_protocol.js
 
 # tweetnacl-js
-$NODE scripts/tweetnacl-js/test.js
+external TweetNaCl-js node scripts/tweetnacl-js/eddsa_protocol.js
 
 # python-ed25519
 pushd "$SOURCE_DIR/scripts/python-ed25519"
@@ -64,16 +65,16 @@ then
     git clone git@github.com:warner/python-ed25519.git
     cd python-ed25519
     git reset --hard d57b8f2c7edffff3419d58443ccc29a4fa399a71
-    git apply -v ../add_test_git.patch
     python3.7 setup.py build
-else
-    cd "./python-ed25519"
+    cd ..
 fi
-python3.7 setup.py test
+export PYTHONPATH=$(echo "$(pwd)"/python-ed25519/build/lib*)
 popd
+external python-ed25519 python3.7 scripts/python-ed25519/eddsa_protocol.py
+unset PYTHONPATH
 
 # PyCA
-python3.7 scripts/pyca-openssl/eddsa_utils.py
+external PyCA python3 scripts/pyca-openssl/eddsa_protocol.py
 
 # LibSodium
 pushd "$SOURCE_DIR/scripts/libsodium"
@@ -91,10 +92,10 @@ then
     make install
     cd ..
 fi
-rm main
-make
-./main
+rm -f protocol
+make protocol
 popd
+external LibSodium scripts/libsodium/protocol
 
 # ref10
 pushd "$SOURCE_DIR/scripts/ref10"
@@ -112,10 +113,10 @@ then
     make install
     cd ..
 fi
-rm main
-make
-./main
+rm -f protocol
+make protocol
 popd
+external ref10 scripts/ref10/protocol
 
 # openssl-3.0
 pushd "$SOURCE_DIR/scripts/openssl_3"
@@ -130,14 +131,6 @@ then
     make install
     cd ..
 fi
-
-./test_script.sh
+gcc -o protocol protocol.c -L"$OPENSSL_INSTALL_DIR"/lib -lcrypto -I"$OPENSSL_INSTALL_DIR"/include
 popd
-}
-
-main > results.md  2>/dev/null
-
-sed -ire  '/^|/!d' results.md
-sort -f results.md -o results.md
-
-cat results.md
+external OpenSSL-3.0 scripts/openssl_3/protocol
@@ -0,0 +1,40 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+// CryptoKit verifier speaking the line protocol of `ed25519-speccheck external`:
+//   cargo run --features libraries -- external CryptoKit swift scripts/ed25519-ios/Ed25519Protocol.swift
+
+import CryptoKit
+import Foundation
+
+/// Decode a hexadecimal string, or return `nil` if it is not one
+func fromHex(_ hex: Substring) -> Data? {
+  guard hex.count % 2 == 0 else { return nil }
+  var data = Data(capacity: hex.count / 2)
+  var index = hex.startIndex
+  while index < hex.endIndex {
+    let next = hex.index(index, offsetBy: 2)
+    guard let byte = UInt8(hex[index..<next], radix: 16) else { return nil }
+    data.append(byte)
+    index = next
+  }
+  return data
+}
+
+func verify(_ line: String) -> Bool {
+  let fields = line.split(separator: " ", omittingEmptySubsequences: false)
+  guard fields.count == 3,
+    let message = fromHex(fields[0]),
+    let pubKeyData = fromHex(fields[1]),
+    let signature = fromHex(fields[2]),
+    let pubKey = try? Curve25519.Signing.PublicKey(rawRepresentation: pubKeyData)
+  else { return false }
+  return pubKey.isValidSignature(signature, for: message)
+}
+
+while let line = readLine() {
+  print(verify(line) ? "1" : "0")
+  fflush(stdout)
+}
@@ -0,0 +1,36 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+import java.io.BufferedReader;
+import java.io.IOException;
+import java.io.InputStreamReader;
+
+/**
+ * ed25519-java or BouncyCastle verifier speaking the line protocol of `ed25519-speccheck external`:
+ *   cargo run --features libraries -- external BouncyCastle java -cp $CLASSPATH ProtocolChecker BouncyCastle
+ **/
+public class ProtocolChecker {
+
+    public static void main(String[] args) throws IOException {
+        if (args.length != 1 || !(args[0].equals("ed25519-java") || args[0].equals("BouncyCastle"))) {
+            System.err.println("usage: ProtocolChecker ed25519-java|BouncyCastle");
+            System.exit(2);
+        }
+        boolean i2p = args[0].equals("ed25519-java");
+
+        BufferedReader reader = new BufferedReader(new InputStreamReader(System.in));
+        String line;
+        while ((line = reader.readLine()) != null) {
+            String[] fields = line.split(" ", -1);
+            boolean accepted = false;
+            if (fields.length == 3) {
+                Ed25519TestCase testCase = new Ed25519TestCase(fields[1], fields[0], fields[2]);
+                accepted = i2p ? testCase.verify_i2p() : testCase.verify_bc();
+            }
+            System.out.println(accepted ? "1" : "0");
+            System.out.flush();
+        }
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+// ed25519-donna verifier speaking the line protocol of `ed25519-speccheck external`:
+//   ./build+run.sh
+//   cargo run --features libraries -- external ed25519-donna scripts/ed25519-signal-donna/build/protocol-donna
+
+#define _GNU_SOURCE
+#include <stdlib.h>
+#include <string.h>
+#include <stdint.h>
+#include <stdio.h>
+
+int crypto_sign_open_modified(
+  unsigned char *m,
+  const unsigned char *sm,unsigned long long smlen,
+  const unsigned char *pk
+  );
+
+#include "libsignal-protocol-c/src/curve25519/curve25519-donna.h"
+#include "libsignal-protocol-c/src/curve25519/ed25519/tests/internal_fast_tests.h"
+
+// Decode the hex string `hex` into `res`, returning its length, or -1 if it is
+// not one
+long hex_string_to_byte_array(const char *hex, unsigned char *res) {
+    size_t len = strlen(hex);
+    if (len % 2 != 0) {
+      return -1;
+    }
+    for (size_t i = 0; i < len / 2; i++) {
+      if (sscanf(hex + 2 * i, "%2hhx", &res[i]) != 1) {
+        return -1;
+      }
+    }
+    return len / 2;
+}
+
+int verify(char *line) {
+    char *msg = strsep(&line, " ");
+    char *pk = strsep(&line, " ");
+    char *sig = strsep(&line, " ");
+    if (sig == NULL || line != NULL
+        || strlen(pk) != 2 * 32
+        || strlen(sig) != 2 * 64) {
+      return 0;
+    }
+
+    /* The below call has a strange API: */
+    /* verifybuf = R || S || message */
+    /* verifybuf2 = internal to next call gets a copy of verifybuf, S gets
+       replaced with pubkey for hashing */
+    unsigned char pk_bytes[32];
+    unsigned char *verifybuf = malloc(64 + strlen(msg) / 2 + 1);
+    unsigned char *verifybuf2 = malloc(64 + strlen(msg) / 2 + 1);
+    long msg_len = hex_string_to_byte_array(msg, verifybuf + 64);
+    int result = msg_len >= 0
+      && hex_string_to_byte_array(pk, pk_bytes) >= 0
+      && hex_string_to_byte_array(sig, verifybuf) >= 0
+      && crypto_sign_open_modified(verifybuf2, verifybuf, 64 + msg_len, pk_bytes) == 0;
+    free(verifybuf);
+    free(verifybuf2);
+    return result;
+}
+
+int main(void) {
+    char *line = NULL;
+    size_t line_cap = 0;
+    ssize_t line_len;
+    while ((line_len = getline(&line, &line_cap, stdin)) != -1) {
+      if (line_len > 0 && line[line_len - 1] == '\n') {
+        line[line_len - 1] = '\0';
+      }
+      printf(verify(line) ? "1\n" : "0\n");
+      fflush(stdout);
+    }
+    free(line);
+    return 0;
+}
@@ -0,0 +1,52 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+// Go verifier speaking the line protocol of `ed25519-speccheck external`:
+//   cargo run --features libraries -- external Go go run scripts/go/eddsa_protocol.go
+
+// +build ignore
+
+package main
+
+import (
+	"bufio"
+	"encoding/hex"
+	"fmt"
+	"golang.org/x/crypto/ed25519"
+	"os"
+	"strings"
+)
+
+func verify(line string) bool {
+	fields := strings.Split(line, " ")
+	if len(fields) != 3 {
+		return false
+	}
+	m_bytes, err := hex.DecodeString(fields[0])
+	if err != nil {
+		return false
+	}
+	pk_bytes, err := hex.DecodeString(fields[1])
+	if err != nil || len(pk_bytes) != ed25519.PublicKeySize {
+		return false
+	}
+	sig_bytes, err := hex.DecodeString(fields[2])
+	if err != nil {
+		return false
+	}
+	return ed25519.Verify(ed25519.PublicKey(pk_bytes), m_bytes, sig_bytes)
+}
+
+func main() {
+	scanner := bufio.NewScanner(os.Stdin)
+	scanner.Buffer(make([]byte, 64*1024), 1024*1024)
+	for scanner.Scan() {
+		if verify(scanner.Text()) {
+			fmt.Println("1")
+		} else {
+			fmt.Println("0")
+		}
+	}
+}
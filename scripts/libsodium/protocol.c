@@ -0,0 +1,72 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+// LibSodium verifier speaking the line protocol of `ed25519-speccheck external`:
+//   make protocol
+//   cargo run --features libraries -- external LibSodium scripts/libsodium/protocol
+
+#define _GNU_SOURCE
+#include <sodium.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Decode the hex string `hex` into `res`, returning its length, or -1 if it is
+// not one
+long hex_string_to_byte_array(const char *hex, unsigned char *res) {
+    size_t len = strlen(hex);
+    if (len % 2 != 0) {
+      return -1;
+    }
+    for (size_t i = 0; i < len / 2; i++) {
+      if (sscanf(hex + 2 * i, "%2hhx", &res[i]) != 1) {
+        return -1;
+      }
+    }
+    return len / 2;
+}
+
+int verify(char *line) {
+    char *msg = strsep(&line, " ");
+    char *pk = strsep(&line, " ");
+    char *sig = strsep(&line, " ");
+    if (sig == NULL || line != NULL
+        || strlen(pk) != 2 * crypto_sign_PUBLICKEYBYTES
+        || strlen(sig) != 2 * crypto_sign_BYTES) {
+      return 0;
+    }
+
+    unsigned char pk_bytes[crypto_sign_PUBLICKEYBYTES];
+    unsigned char sig_bytes[crypto_sign_BYTES];
+    unsigned char *msg_bytes = malloc(strlen(msg) / 2 + 1);
+    long msg_len = hex_string_to_byte_array(msg, msg_bytes);
+    int result = msg_len >= 0
+      && hex_string_to_byte_array(pk, pk_bytes) >= 0
+      && hex_string_to_byte_array(sig, sig_bytes) >= 0
+      && crypto_sign_verify_detached(sig_bytes, msg_bytes, msg_len, pk_bytes) == 0;
+    free(msg_bytes);
+    return result;
+}
+
+int main(void) {
+    if (sodium_init() < 0) {
+      /* panic! the library couldn't be initialized, it is not safe to use */
+      fprintf(stderr, "PANIC \n");
+      return 1;
+    }
+
+    char *line = NULL;
+    size_t line_cap = 0;
+    ssize_t line_len;
+    while ((line_len = getline(&line, &line_cap, stdin)) != -1) {
+      if (line_len > 0 && line[line_len - 1] == '\n') {
+        line[line_len - 1] = '\0';
+      }
+      printf(verify(line) ? "1\n" : "0\n");
+      fflush(stdout);
+    }
+    free(line);
+    return 0;
+}
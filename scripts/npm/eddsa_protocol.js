@@ -0,0 +1,37 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+// Node's crypto verifier speaking the line protocol of `ed25519-speccheck external`:
+//   cargo run --features libraries -- external npm node scripts/npm/eddsa_protocol.js
+const crypto = require("crypto");
+const readline = require("readline");
+
+function toPem(hexKey) {
+  let buf1 = Buffer.from("302a300506032b6570032100", "hex");
+  let buf2 = Buffer.from(hexKey, "hex");
+  let keyBuf = Buffer.from(
+    "-----BEGIN PUBLIC KEY-----\n" +
+      Buffer.concat([buf1, buf2]).toString("base64") +
+      "\n-----END PUBLIC KEY-----"
+  );
+
+  return crypto.createPublicKey(keyBuf);
+}
+
+readline.createInterface({ input: process.stdin }).on("line", (line) => {
+  let [message, pub_key, signature] = line.split(" ");
+  let accepted;
+  try {
+    accepted = crypto.verify(
+      null,
+      Buffer.from(message, "hex"),
+      toPem(pub_key),
+      Buffer.from(signature, "hex")
+    );
+  } catch (e) {
+    accepted = false;
+  }
+  process.stdout.write(accepted ? "1\n" : "0\n");
+});
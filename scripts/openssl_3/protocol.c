@@ -0,0 +1,81 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+// OpenSSL 3.0 verifier speaking the line protocol of `ed25519-speccheck external`:
+//   ./test_script.sh
+//   cargo run --features libraries -- external OpenSSL-3.0 scripts/openssl_3/protocol
+
+#define _GNU_SOURCE
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <openssl/evp.h>
+#include <openssl/objects.h>
+
+// Decode the hex string `hex` into `res`, returning its length, or -1 if it is
+// not one
+long hex_string_to_byte_array(const char *hex, unsigned char *res) {
+    size_t len = strlen(hex);
+    if (len % 2 != 0) {
+      return -1;
+    }
+    for (size_t i = 0; i < len / 2; i++) {
+      if (sscanf(hex + 2 * i, "%2hhx", &res[i]) != 1) {
+        return -1;
+      }
+    }
+    return len / 2;
+}
+
+int verify_bytes(const unsigned char *msg, long msg_len,
+                 const unsigned char *pk, const unsigned char *sig) {
+    EVP_PKEY *ed_pkey = EVP_PKEY_new_raw_public_key(NID_ED25519, NULL, pk, 32);
+    if (ed_pkey == NULL) {
+      return 0;
+    }
+    EVP_MD_CTX *ctx = EVP_MD_CTX_new();
+    int result = EVP_DigestVerifyInit(ctx, NULL, NULL, NULL, ed_pkey) == 1
+      && EVP_DigestVerify(ctx, sig, 64, msg, msg_len) == 1;
+    EVP_MD_CTX_free(ctx);
+    EVP_PKEY_free(ed_pkey);
+    return result;
+}
+
+int verify(char *line) {
+    char *msg = strsep(&line, " ");
+    char *pk = strsep(&line, " ");
+    char *sig = strsep(&line, " ");
+    if (sig == NULL || line != NULL
+        || strlen(pk) != 2 * 32
+        || strlen(sig) != 2 * 64) {
+      return 0;
+    }
+
+    unsigned char pk_bytes[32];
+    unsigned char sig_bytes[64];
+    unsigned char *msg_bytes = malloc(strlen(msg) / 2 + 1);
+    long msg_len = hex_string_to_byte_array(msg, msg_bytes);
+    int result = msg_len >= 0
+      && hex_string_to_byte_array(pk, pk_bytes) >= 0
+      && hex_string_to_byte_array(sig, sig_bytes) >= 0
+      && verify_bytes(msg_bytes, msg_len, pk_bytes, sig_bytes);
+    free(msg_bytes);
+    return result;
+}
+
+int main(void) {
+    char *line = NULL;
+    size_t line_cap = 0;
+    ssize_t line_len;
+    while ((line_len = getline(&line, &line_cap, stdin)) != -1) {
+      if (line_len > 0 && line[line_len - 1] == '\n') {
+        line[line_len - 1] = '\0';
+      }
+      printf(verify(line) ? "1\n" : "0\n");
+      fflush(stdout);
+    }
+    free(line);
+    return 0;
+}
@@ -8,4 +8,5 @@
 export OPENSSL_PATH=./openssl-build
 
 gcc -o main main.c -L"$OPENSSL_PATH"/lib -lssl -lcrypto -I"$OPENSSL_PATH"/include
+gcc -o protocol protocol.c -L"$OPENSSL_PATH"/lib -lcrypto -I"$OPENSSL_PATH"/include
 ./main
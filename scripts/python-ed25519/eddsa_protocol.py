@@ -0,0 +1,20 @@
+# Copyright (c) Facebook, Inc. and its affiliates.
+#
+# This source code is licensed under the APACHE 2.0 license found in
+# the LICENSE file in the root directory of this source tree.
+
+# python-ed25519 verifier speaking the line protocol of `ed25519-speccheck external`:
+#   cargo run --features libraries -- external python-ed25519 python3 scripts/python-ed25519/eddsa_protocol.py
+import sys
+
+import ed25519
+
+if __name__ == "__main__":
+    for line in sys.stdin:
+        try:
+            msg, pub_key, sig = line.rstrip('\n').split(' ')
+            ed25519.VerifyingKey(bytes.fromhex(pub_key)).verify(
+                bytes.fromhex(sig), bytes.fromhex(msg))
+            print('1', flush=True)
+        except Exception:
+            print('0', flush=True)
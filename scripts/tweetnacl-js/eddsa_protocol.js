@@ -0,0 +1,25 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+// TweetNaCl-js verifier speaking the line protocol of `ed25519-speccheck external`:
+//   cargo run --features libraries -- external TweetNaCl-js node scripts/tweetnacl-js/eddsa_protocol.js
+const nacl = require("tweetnacl");
+const readline = require("readline");
+
+const fromHexString = hexString =>
+      new Uint8Array(Buffer.from(hexString, "hex"));
+
+readline.createInterface({ input: process.stdin }).on("line", (line) => {
+  let [message, pub_key, signature] = line.split(" ");
+  let accepted;
+  try {
+    accepted = nacl.sign.detached.verify(fromHexString(message),
+                                         fromHexString(signature),
+                                         fromHexString(pub_key));
+  } catch (e) {
+    accepted = false;
+  }
+  process.stdout.write(accepted ? "1\n" : "0\n");
+});
@@ -0,0 +1,165 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Annotate an arbitrary (message, public key, signature) triple: how its
+//! components classify, how it hashes, and which reference policies accept
+//! it and why the others do not. This is where to start when two verifiers
+//! disagree about a signature.
+
+use anyhow::Result;
+use core::fmt;
+use std::convert::TryInto;
+
+use crate::grid::{PointClass, ScalarClass};
+use crate::inspect::{inspect_point, PointReport};
+use crate::policy::REFERENCE_POLICIES;
+use crate::{check_slice_size, compute_hram, compute_hram_with_arrays, deserialize_point};
+
+pub struct Analysis {
+    pub message: Vec<u8>,
+    pub pub_key: PointReport,
+    pub r: PointReport,
+    pub s: [u8; 32],
+    pub s_class: ScalarClass,
+    /// k over the bytes of R and A as received
+    pub raw_hash: [u8; 32],
+    /// k over the canonical encodings of R and A, if both decode
+    pub reencoded_hash: Option<[u8; 32]>,
+    /// The outcome under each reference policy, with the reason for rejecting
+    pub outcomes: Vec<(&'static str, Result<(), String>)>,
+}
+
+pub fn analyze(message: &[u8], pub_key: &[u8], signature: &[u8]) -> Result<Analysis> {
+    let pub_key = check_slice_size(pub_key, 32, "pub_key")?;
+    let signature = check_slice_size(signature, 64, "signature")?;
+    let (r_bytes, s_bytes) = signature.split_at(32);
+
+    let raw_hash = compute_hram_with_arrays(message, pub_key, r_bytes).to_bytes();
+    let reencoded_hash = match (deserialize_point(pub_key), deserialize_point(r_bytes)) {
+        (Ok(a), Ok(r)) => Some(compute_hram(message, &a, &r).to_bytes()),
+        _ => None,
+    };
+    let outcomes = REFERENCE_POLICIES
+        .iter()
+        .map(|p| {
+            (
+                p.name,
+                p.verify(message, pub_key, signature)
+                    .map_err(|e| e.to_string()),
+            )
+        })
+        .collect();
+    let s: [u8; 32] = s_bytes.try_into()?;
+
+    Ok(Analysis {
+        message: message.to_vec(),
+        pub_key: inspect_point(pub_key)?,
+        r: inspect_point(r_bytes)?,
+        s,
+        s_class: ScalarClass::of(&s),
+        raw_hash,
+        reencoded_hash,
+        outcomes,
+    })
+}
+
+/// e.g. `mixed, order 8L, torsion component EIGHT_TORSION[3], non-canonical`
+fn describe_point(report: &PointReport) -> String {
+    let decoded = match &report.decoded {
+        Some(decoded) => decoded,
+        None => return "does not decode".to_string(),
+    };
+    let class = PointClass::of(&deserialize_point(&report.encoding).unwrap());
+    format!(
+        "{}, order {}, torsion component EIGHT_TORSION[{}], {}",
+        class,
+        report.order().unwrap(),
+        decoded.torsion_index,
+        if report.is_canonical() {
+            "canonical"
+        } else {
+            "non-canonical"
+        }
+    )
+}
+
+impl Analysis {
+    /// The reference policies accepting the signature
+    pub fn accepted_by(&self) -> Vec<&'static str> {
+        self.outcomes
+            .iter()
+            .filter(|(_, outcome)| outcome.is_ok())
+            .map(|(name, _)| *name)
+            .collect()
+    }
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "message:        {}", hex::encode(&self.message))?;
+        writeln!(f, "A:              {}", hex::encode(&self.pub_key.encoding))?;
+        writeln!(f, "                {}", describe_point(&self.pub_key))?;
+        writeln!(f, "R:              {}", hex::encode(&self.r.encoding))?;
+        writeln!(f, "                {}", describe_point(&self.r))?;
+        writeln!(f, "S:              {}", hex::encode(&self.s))?;
+        writeln!(f, "                {}", self.s_class)?;
+        writeln!(f, "k (raw):        {}", hex::encode(&self.raw_hash))?;
+        match self.reencoded_hash {
+            Some(hash) if hash == self.raw_hash => writeln!(f, "k (re-encoded): same")?,
+            Some(hash) => writeln!(f, "k (re-encoded): {}", hex::encode(&hash))?,
+            None => writeln!(f, "k (re-encoded): n/a")?,
+        }
+        for (idx, (name, outcome)) in self.outcomes.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            match outcome {
+                Ok(()) => write!(f, "{:<24}accepted", name)?,
+                Err(reason) => write!(f, "{:<24}rejected: {}", name, reason)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_test_vectors;
+
+    #[test]
+    fn test_analyze_test_vectors() {
+        let vec = generate_test_vectors();
+        for tv in vec.iter() {
+            let analysis = analyze(&tv.message, &tv.pub_key, &tv.signature).unwrap();
+            for (policy, (name, outcome)) in REFERENCE_POLICIES.iter().zip(analysis.outcomes) {
+                assert_eq!(policy.name, name);
+                assert_eq!(
+                    policy
+                        .verify(&tv.message, &tv.pub_key, &tv.signature)
+                        .is_ok(),
+                    outcome.is_ok()
+                );
+            }
+        }
+
+        // #0: S = 0, small A and R
+        let analysis = analyze(&vec[0].message, &vec[0].pub_key, &vec[0].signature).unwrap();
+        assert_eq!(analysis.s_class, ScalarClass::Zero);
+        assert_eq!(analysis.reencoded_hash, Some(analysis.raw_hash));
+        let text = analysis.to_string();
+        assert!(text.contains("A has small order"), "{}", text);
+
+        // #6: S > L
+        let analysis = analyze(&vec[6].message, &vec[6].pub_key, &vec[6].signature).unwrap();
+        assert_eq!(analysis.s_class, ScalarClass::Unreduced);
+
+        // #9: non-canonical R, hashed as received
+        let analysis = analyze(&vec[9].message, &vec[9].pub_key, &vec[9].signature).unwrap();
+        assert!(!analysis.r.is_canonical());
+        assert_ne!(analysis.reencoded_hash, Some(analysis.raw_hash));
+        assert!(analyze(&vec[9].message, &vec[9].pub_key, &[0u8; 63]).is_err());
+    }
+}
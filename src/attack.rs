@@ -0,0 +1,294 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Secret key recovery from signatures a verifier should never have accepted.
+//!
+//! For a signature `(R, S)` under `A = [a]B + T_A`, cofactored verification
+//! checks `[8]S = [8](r + k * a)` where `R = [r]B + T_R`. If R has no
+//! prime-order component (small order R), then `r = 0` and `a = S / k`. If
+//! two signatures share R, then `a = (S1 - S2) / (k1 - k2)`.
+//!
+//! In both cases, the recovered `a` is only meaningful if `[8][a]B = [8]A`,
+//! i.e. if it matches the prime-order component of the public key.
+
+use anyhow::{anyhow, Result};
+use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT, scalar::Scalar};
+
+use crate::signing::recover_from_double_public_key;
+use crate::{
+    check_slice_size, compute_hram, compute_hram_with_arrays, deserialize_point, deserialize_scalar,
+};
+
+/// A signed message, as raw bytes
+pub struct SignedMessage<'a> {
+    pub message: &'a [u8],
+    pub pub_key: &'a [u8],
+    pub signature: &'a [u8],
+}
+
+/// The hash `k = H(R || A || M)`, over the raw bytes of R and A, or over
+/// their canonical re-encodings if `reserialize` is set
+fn hram(sm: &SignedMessage, reserialize: bool) -> Result<Scalar> {
+    let r_bytes = &check_slice_size(sm.signature, 64, "signature")?[..32];
+    let pub_key = check_slice_size(sm.pub_key, 32, "pub_key")?;
+    if reserialize {
+        Ok(compute_hram(
+            sm.message,
+            &deserialize_point(pub_key)?,
+            &deserialize_point(r_bytes)?,
+        ))
+    } else {
+        Ok(compute_hram_with_arrays(sm.message, pub_key, r_bytes))
+    }
+}
+
+/// Whether `[a]B` is the prime-order component of `pub_key`
+pub fn matches_pub_key(a: &Scalar, pub_key: &[u8]) -> Result<bool> {
+    let pub_key = deserialize_point(pub_key)?;
+    Ok((a * ED25519_BASEPOINT_POINT).mul_by_cofactor() == pub_key.mul_by_cofactor())
+}
+
+/// Recover `a` from a single signature whose R has small order, trying the
+/// hash over both the raw and the re-encoded R and A.
+pub fn recover_from_small_r(sm: &SignedMessage) -> Result<Scalar> {
+    let r = deserialize_point(&check_slice_size(sm.signature, 64, "signature")?[..32])?;
+    if !r.is_small_order() {
+        return Err(anyhow!(
+            "R has a prime-order component: S = r + k * a with an unknown nonce r, \
+             a single signature does not reveal a"
+        ));
+    }
+    let pub_key = deserialize_point(check_slice_size(sm.pub_key, 32, "pub_key")?)?;
+    if pub_key.is_small_order() {
+        return Err(anyhow!(
+            "A has small order: there is no secret scalar to recover"
+        ));
+    }
+    let s = deserialize_scalar(&sm.signature[32..])?.reduce();
+
+    for reserialize in [false, true].iter() {
+        let k = hram(sm, *reserialize)?;
+        if k == Scalar::zero() {
+            continue;
+        }
+        let a = s * k.invert();
+        if matches_pub_key(&a, sm.pub_key)? {
+            return Ok(a);
+        }
+    }
+    Err(anyhow!(
+        "S / k does not match the prime-order component of A for either hash: \
+         the signature does not verify under cofactored rules"
+    ))
+}
+
+/// Whether `a` matches the public key of `sm1` or `sm2`, a public key that
+/// does not decode matching nothing
+fn matches_either(a: &Scalar, sm1: &SignedMessage, sm2: &SignedMessage) -> bool {
+    matches_pub_key(a, sm1.pub_key).unwrap_or(false)
+        || matches_pub_key(a, sm2.pub_key).unwrap_or(false)
+}
+
+/// Recover `a` from signatures that share R, made with the same secret key
+/// (possibly over distinct public keys, as with a double public key oracle).
+pub fn recover_from_shared_r(sms: &[SignedMessage]) -> Result<Scalar> {
+    for (i, sm1) in sms.iter().enumerate() {
+        for sm2 in sms[i + 1..].iter() {
+            check_slice_size(sm1.signature, 64, "signature")?;
+            check_slice_size(sm2.signature, 64, "signature")?;
+            if sm1.signature[..32] != sm2.signature[..32] {
+                continue;
+            }
+            // the double public key oracle: one message, two public keys
+            if sm1.message == sm2.message {
+                if let Ok(a) = recover_from_double_public_key(
+                    sm1.message,
+                    sm1.pub_key,
+                    sm1.signature,
+                    sm2.pub_key,
+                    sm2.signature,
+                ) {
+                    if matches_either(&a, sm1, sm2) {
+                        return Ok(a);
+                    }
+                }
+            }
+            // otherwise, a nonce reused across messages, or a verifier
+            // hashing the re-encoded points
+            let s1 = deserialize_scalar(&sm1.signature[32..])?.reduce();
+            let s2 = deserialize_scalar(&sm2.signature[32..])?.reduce();
+            for reserialize in [false, true].iter() {
+                // a public key that does not decode only rules out this pair
+                let (k1, k2) = match (hram(sm1, *reserialize), hram(sm2, *reserialize)) {
+                    (Ok(k1), Ok(k2)) => (k1, k2),
+                    _ => continue,
+                };
+                if k1 == k2 {
+                    continue;
+                }
+                let a = (s1 - s2) * (k1 - k2).invert();
+                if matches_either(&a, sm1, sm2) {
+                    return Ok(a);
+                }
+            }
+        }
+    }
+    Err(anyhow!(
+        "no two signatures share R and yield a key matching their public keys"
+    ))
+}
+
+/// Try every recovery against `sms`, and describe the outcome for each
+/// signature and for the set as a whole.
+pub fn attack(sms: &[SignedMessage]) -> (Option<Scalar>, Vec<String>) {
+    let mut report = Vec::new();
+    let mut recovered = None;
+    for (i, sm) in sms.iter().enumerate() {
+        match recover_from_small_r(sm) {
+            Ok(a) => {
+                report.push(format!(
+                    "signature #{}: small order R, recovered a = {}",
+                    i,
+                    hex::encode(a.as_bytes())
+                ));
+                recovered = Some(a);
+            }
+            Err(e) => report.push(format!("signature #{}: {}", i, e)),
+        }
+    }
+    if sms.len() > 1 {
+        match recover_from_shared_r(sms) {
+            Ok(a) => {
+                report.push(format!(
+                    "shared R: recovered a = {}",
+                    hex::encode(a.as_bytes())
+                ));
+                recovered = Some(a);
+            }
+            Err(e) => report.push(format!("shared R: {}", e)),
+        }
+    }
+    (recovered, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::random_nonzero_scalar;
+    use crate::signing::expand_seed;
+    use crate::{generate_test_vectors, new_rng, serialize_signature};
+    use rand::RngCore;
+
+    fn signed_message(tv: &crate::TestVector) -> SignedMessage<'_> {
+        SignedMessage {
+            message: &tv.message,
+            pub_key: &tv.pub_key,
+            signature: &tv.signature,
+        }
+    }
+
+    #[test]
+    fn test_small_r_recovery() {
+        let vec = generate_test_vectors();
+        // #2 has a small R, #8 and #9 a non-canonical small R, hashed
+        // respectively re-encoded and as-is
+        for i in [2, 8, 9].iter() {
+            let a = recover_from_small_r(&signed_message(&vec[*i])).unwrap();
+            assert!(matches_pub_key(&a, &vec[*i].pub_key).unwrap());
+        }
+
+        // #0 has small A, #3 a mixed R
+        for i in [0, 3].iter() {
+            assert!(recover_from_small_r(&signed_message(&vec[*i])).is_err());
+        }
+    }
+
+    #[test]
+    fn test_shared_r_recovery() {
+        let vec = generate_test_vectors();
+        // #6 and #7 are the same signature with S + kL
+        assert!(
+            recover_from_shared_r(&[signed_message(&vec[6]), signed_message(&vec[7])]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_double_public_key_recovery() {
+        // one message signed for two public keys, by a double public key oracle
+        let mut rng = new_rng();
+        let mut seed = [0u8; 32];
+        rng.fill_bytes(&mut seed);
+        let expanded = expand_seed(&seed);
+        let pub_key = expanded.public_key();
+        let other_pub_key = (random_nonzero_scalar(&mut rng) * ED25519_BASEPOINT_POINT)
+            .compress()
+            .to_bytes();
+        let message = b"double public key";
+        let signature = expanded.sign_with_public_key(&pub_key, message);
+        let other_signature = expanded.sign_with_public_key(&other_pub_key, message);
+
+        let a = recover_from_shared_r(&[
+            SignedMessage {
+                message,
+                pub_key: &pub_key,
+                signature: &signature,
+            },
+            SignedMessage {
+                message,
+                pub_key: &other_pub_key,
+                signature: &other_signature,
+            },
+        ])
+        .unwrap();
+        assert_eq!(a, expanded.scalar().reduce());
+    }
+
+    #[test]
+    fn test_reused_nonce_recovery() {
+        // two messages signed with one key and one nonce
+        let mut rng = new_rng();
+        let a = random_nonzero_scalar(&mut rng);
+        let r = random_nonzero_scalar(&mut rng);
+        let pub_key_point = a * ED25519_BASEPOINT_POINT;
+        let pub_key = pub_key_point.compress().to_bytes();
+        let big_r = r * ED25519_BASEPOINT_POINT;
+        let messages: [&[u8]; 2] = [b"first message", b"second message"];
+        let signatures: Vec<Vec<u8>> = messages
+            .iter()
+            .map(|m| {
+                let s = r + compute_hram(m, &pub_key_point, &big_r) * a;
+                serialize_signature(&big_r, &s)
+            })
+            .collect();
+        let signed = |i: usize| SignedMessage {
+            message: messages[i],
+            pub_key: &pub_key,
+            signature: &signatures[i],
+        };
+        assert_eq!(recover_from_shared_r(&[signed(0), signed(1)]).unwrap(), a);
+        let (recovered, report) = attack(&[signed(0), signed(1)]);
+        assert_eq!(recovered, Some(a));
+        assert!(report[2].starts_with("shared R: recovered"), "{:?}", report);
+
+        // a public key that does not decode does not end the search
+        let undecodable = (0u8..)
+            .map(|y| {
+                let mut bytes = [0u8; 32];
+                bytes[0] = y;
+                bytes
+            })
+            .find(|bytes| deserialize_point(bytes).is_err())
+            .unwrap();
+        let broken = SignedMessage {
+            message: b"third message",
+            pub_key: &undecodable,
+            signature: &signatures[0],
+        };
+        assert_eq!(
+            recover_from_shared_r(&[broken, signed(0), signed(1)]).unwrap(),
+            a
+        );
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Reading and writing test vectors in each supported format.
+//!
+//! `json` is the format of `cases.json`, and `txt` that of `cases.txt`: the
+//! number of vectors, then `msg=`, `pbk=` and `sig=` lines in hex for each.
+//! `rfc8032` and `wycheproof` (see `import`) can only be read.
+
+use anyhow::{anyhow, Context, Result};
+use std::convert::TryInto;
+
+use crate::import;
+use crate::TestVector;
+
+pub const FORMATS: [&str; 4] = ["json", "txt", "rfc8032", "wycheproof"];
+
+/// `vectors` in the format of `cases.txt`
+pub fn to_txt(vectors: &[TestVector]) -> String {
+    let mut out = vectors.len().to_string();
+    for tv in vectors.iter() {
+        out.push_str("\nmsg=");
+        out.push_str(&hex::encode(&tv.message));
+        out.push_str("\npbk=");
+        out.push_str(&hex::encode(&tv.pub_key));
+        out.push_str("\nsig=");
+        out.push_str(&hex::encode(&tv.signature));
+    }
+    out
+}
+
+/// Parse vectors in the format of `cases.txt`
+pub fn from_txt(txt: &str) -> Result<Vec<TestVector>> {
+    let mut lines = txt
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let count: usize = match lines.next() {
+        Some((_, line)) => line.trim().parse().context("invalid vector count")?,
+        None => return Err(anyhow!("empty file")),
+    };
+
+    let mut field = |prefix: &str| -> Result<Vec<u8>> {
+        let (idx, line) = lines
+            .next()
+            .ok_or_else(|| anyhow!("expected {} vectors, missing {}", count, prefix))?;
+        let value = line
+            .trim()
+            .strip_prefix(prefix)
+            .ok_or_else(|| anyhow!("line {}: expected {}", idx + 1, prefix))?;
+        hex::decode(value).with_context(|| format!("line {}", idx + 1))
+    };
+    let mut vec = Vec::with_capacity(count);
+    for _ in 0..count {
+        let message = field("msg=")?;
+        let pub_key = field("pbk=")?;
+        let signature = field("sig=")?;
+        vec.push(TestVector {
+            message,
+            pub_key: pub_key
+                .as_slice()
+                .try_into()
+                .map_err(|_| anyhow!("public key {} is not 32 bytes", hex::encode(&pub_key)))?,
+            signature,
+        });
+    }
+    if let Some((idx, _)) = lines.next() {
+        return Err(anyhow!("line {}: more than {} vectors", idx + 1, count));
+    }
+    Ok(vec)
+}
+
+/// Read vectors in `format`
+pub fn read(format: &str, contents: &str) -> Result<Vec<TestVector>> {
+    let imported = match format {
+        "json" => return Ok(serde_json::from_str(contents)?),
+        "txt" => return from_txt(contents),
+        "rfc8032" => import::load_rfc8032(contents)?,
+        "wycheproof" => import::load_wycheproof(contents)?,
+        _ => {
+            return Err(anyhow!(
+                "unknown format {}, expected one of {:?}",
+                format,
+                FORMATS
+            ))
+        }
+    };
+    Ok(imported
+        .into_iter()
+        .map(|imported| imported.vector)
+        .collect())
+}
+
+/// Write vectors in `format`
+pub fn write(format: &str, vectors: &[TestVector]) -> Result<String> {
+    match format {
+        "json" => Ok(serde_json::to_string(vectors)?),
+        "txt" => Ok(to_txt(vectors)),
+        _ => Err(anyhow!("cannot write format {}, only json and txt", format)),
+    }
+}
+
+/// Convert `input` from format `from` to format `to`
+pub fn convert(from: &str, input: &str, to: &str) -> Result<String> {
+    write(to, &read(from, input)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_test_vectors;
+
+    #[test]
+    fn test_round_trips() {
+        let vec = generate_test_vectors();
+        let json = write("json", &vec).unwrap();
+        let txt = write("txt", &vec).unwrap();
+        assert_eq!(read("json", &json).unwrap(), vec);
+        assert_eq!(read("txt", &txt).unwrap(), vec);
+        assert_eq!(convert("json", &json, "txt").unwrap(), txt);
+        assert_eq!(convert("txt", &txt, "json").unwrap(), json);
+
+        // vectors of any message and signature length
+        let odd = vec![
+            TestVector {
+                message: vec![],
+                pub_key: [7u8; 32],
+                signature: vec![1, 2, 3],
+            },
+            TestVector {
+                message: vec![0xab; 1000],
+                pub_key: [0u8; 32],
+                signature: vec![],
+            },
+        ];
+        assert_eq!(read("txt", &to_txt(&odd)).unwrap(), odd);
+        assert_eq!(read("json", &write("json", &odd).unwrap()).unwrap(), odd);
+        // a trailing newline is fine
+        assert_eq!(read("txt", &format!("{}\n", to_txt(&odd))).unwrap(), odd);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let txt = to_txt(&generate_test_vectors());
+        assert!(from_txt("").is_err());
+        assert!(from_txt(&txt.replacen("12", "13", 1)).is_err());
+        assert!(from_txt(&txt.replacen("12", "11", 1)).is_err());
+        assert!(from_txt(&txt.replacen("pbk=", "pk=", 1)).is_err());
+        assert!(from_txt(&txt.replacen("sig=", "sig=zz", 1)).is_err());
+        assert!(read(
+            "json",
+            r#"[{"message": "", "pub_key": "00", "signature": ""}]"#
+        )
+        .is_err());
+        assert!(read("json", r#"[{"message": "", "signature": ""}]"#).is_err());
+        assert!(read("csv", "").is_err());
+        assert!(write("rfc8032", &[]).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Expected verification results of each library over the test vectors.
+//!
+//! The manifest `expected_results.json` has one entry per library of
+//! `results.md`, with its row: one column per vector of
+//! `generate_test_vectors`, `V` if the library accepts the vector and `X` if
+//! it rejects it. Entries may give the `command` of a verifier speaking the
+//! protocol of `external`, which `check` then re-runs.
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+use crate::external;
+use crate::libraries::RUST_LIBRARIES;
+use crate::results::ResultRow;
+
+const MANIFEST: &str = include_str!("../expected_results.json");
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ManifestEntry {
+    pub library: String,
+    pub expected: String,
+    /// An external verifier speaking the line protocol, with its arguments
+    #[serde(default)]
+    pub command: Vec<String>,
+}
+
+pub fn manifest() -> Vec<ManifestEntry> {
+    serde_json::from_str(MANIFEST).expect("expected_results.json is invalid")
+}
+
+/// The expected row of `library`
+pub fn expected_row(library: &str) -> Option<String> {
+    manifest()
+        .into_iter()
+        .find(|entry| entry.library == library)
+        .map(|entry| entry.expected)
+}
+
+/// Compare the results of `library` to its expected row, listing every vector
+/// whose result changed.
+pub fn check_row(library: &str, actual: &str) -> Result<()> {
+    let expected =
+        expected_row(library).ok_or_else(|| anyhow!("no expected results for {}", library))?;
+    diff_rows(library, &expected, actual)
+}
+
+fn diff_rows(library: &str, expected: &str, actual: &str) -> Result<()> {
+    if expected.len() != actual.len() {
+        return Err(anyhow!(
+            "{}: expected results for {} vectors, got {}",
+            library,
+            expected.len(),
+            actual.len()
+        ));
+    }
+    let changes: Vec<String> = expected
+        .chars()
+        .zip(actual.chars())
+        .enumerate()
+        .filter(|(_, (e, a))| e != a)
+        .map(|(i, (e, a))| format!("  vector #{}: expected {}, got {}", i, e, a))
+        .collect();
+    if changes.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "{} changed behavior\n  expected {}\n  actual   {}\n{}",
+            library,
+            expected,
+            actual,
+            changes.join("\n")
+        ))
+    }
+}
+
+/// Re-run the harness of `entry`, if it has one, and compare its results to
+/// the expected row. Returns `Ok(None)` for libraries without a harness.
+pub fn check_entry(entry: &ManifestEntry) -> Result<Option<ResultRow>> {
+    let row = if let Some(library) = RUST_LIBRARIES.iter().find(|l| l.name == entry.library) {
+        ResultRow::run(library, &crate::generate_test_vectors())
+    } else if let Some((command, args)) = entry.command.split_first() {
+        external::run_external(&entry.library, command, args)?
+    } else {
+        return Ok(None);
+    };
+    diff_rows(&entry.library, &entry.expected, &row.to_row_string())?;
+    Ok(Some(row))
+}
+
+/// Re-run the harness of each library in `libraries` (all of them if empty),
+/// and report whether its behavior changed.
+pub fn check(libraries: &[String]) -> Result<()> {
+    let manifest = manifest();
+    for name in libraries.iter() {
+        if !manifest.iter().any(|entry| &entry.library == name) {
+            return Err(anyhow!("{} is not in expected_results.json", name));
+        }
+    }
+
+    let mut changed = 0;
+    for entry in manifest
+        .iter()
+        .filter(|entry| libraries.is_empty() || libraries.contains(&entry.library))
+    {
+        match check_entry(entry) {
+            Ok(Some(_)) => println!("{}: ok", entry.library),
+            Ok(None) => println!("{}: no harness, skipped", entry.library),
+            Err(e) => {
+                println!("{}", e);
+                changed += 1;
+            }
+        }
+    }
+    if changed > 0 {
+        Err(anyhow!("{} library(ies) changed behavior", changed))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_results_match_results_md() {
+        let rows: Vec<(String, String)> = include_str!("../results.md")
+            .lines()
+            .filter_map(|line| {
+                let mut cells = line.trim_matches('|').split('|').map(str::trim);
+                let name = cells.next()?.to_string();
+                Some((name, cells.collect()))
+            })
+            .collect();
+        assert_eq!(rows.len(), manifest().len());
+        for (name, row) in rows.iter() {
+            assert_eq!(expected_row(name).as_ref(), Some(row), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_check_row() {
+        assert!(check_row("Zebra", "VVVVVVXXXVVV").is_ok());
+        let err = check_row("Zebra", "VVVVVVXXXVVX").unwrap_err().to_string();
+        assert!(err.contains("vector #11: expected V, got X"), "{}", err);
+        assert!(check_row("Zebra", "VVV").is_err());
+        assert!(check_row("unknown", "VVVVVVXXXVVV").is_err());
+    }
+
+    #[test]
+    fn test_check_entry() {
+        let entry = |expected: &str, command: &str| ManifestEntry {
+            library: "sh".to_string(),
+            expected: expected.to_string(),
+            command: vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                format!("while read l; do echo {}; done", command),
+            ],
+        };
+        assert!(check_entry(&entry("VVVVVVVVVVVV", "1")).unwrap().is_some());
+        let err = check_entry(&entry("VVVVVVVVVVVV", "0")).unwrap_err();
+        assert!(err.to_string().contains("sh changed behavior"), "{}", err);
+        let no_harness = ManifestEntry {
+            command: vec![],
+            ..entry("", "")
+        };
+        assert!(check_entry(&no_harness).unwrap().is_none());
+        assert!(check(&["unknown".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Line-based protocol for external verifiers.
+//!
+//! The runner starts the verifier as a subprocess and writes one line per
+//! test vector to its stdin: the message, public key and signature in hex,
+//! separated by single spaces (`<msg> <pk> <sig>`, the message possibly
+//! empty). The verifier answers each line with a line holding `1` if it
+//! accepts the signature and `0` otherwise, and exits once stdin is closed.
+
+use anyhow::{anyhow, Context, Result};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+
+use crate::results::ResultRow;
+use crate::{generate_test_vectors, TestVector};
+
+/// The request line for `tv`
+pub fn request_line(tv: &TestVector) -> String {
+    format!(
+        "{} {} {}\n",
+        hex::encode(&tv.message),
+        hex::encode(&tv.pub_key),
+        hex::encode(&tv.signature)
+    )
+}
+
+/// Parse a response line: `1` for accepted, `0` for rejected
+pub fn parse_response(line: &str) -> Result<bool> {
+    match line.trim() {
+        "1" => Ok(true),
+        "0" => Ok(false),
+        other => Err(anyhow!("invalid response {:?}, expected 1 or 0", other)),
+    }
+}
+
+/// Read the responses of `command`, failing on the first invalid one, or
+/// once it answers more than `count` lines
+fn read_responses(stdout: impl BufRead, command: &str, count: usize) -> Result<Vec<bool>> {
+    let mut accepted = Vec::with_capacity(count);
+    for (i, line) in stdout.lines().enumerate() {
+        if i >= count {
+            return Err(anyhow!("{} answered more than {} lines", command, count));
+        }
+        accepted.push(parse_response(&line?).with_context(|| format!("vector #{}", i))?);
+    }
+    Ok(accepted)
+}
+
+/// Run `command` with `args` over `vectors` per the protocol
+pub fn run_protocol(command: &str, args: &[String], vectors: &[TestVector]) -> Result<Vec<bool>> {
+    let mut child = Command::new(command)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("could not start {}", command))?;
+
+    // Write from another thread, so that a verifier answering as it reads
+    // cannot block on a full stdout pipe
+    let requests: String = vectors.iter().map(request_line).collect();
+    let mut stdin = child.stdin.take().unwrap();
+    let writer = std::thread::spawn(move || stdin.write_all(requests.as_bytes()));
+
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    let accepted = match read_responses(stdout, command, vectors.len()) {
+        Ok(accepted) => accepted,
+        Err(e) => {
+            // Don't leave the verifier running, nor a zombie behind
+            let _ = child.kill();
+            let _ = child.wait();
+            let _ = writer.join();
+            return Err(e);
+        }
+    };
+    writer
+        .join()
+        .map_err(|_| anyhow!("writing to {} panicked", command))?
+        .with_context(|| format!("could not write to {}", command))?;
+
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(anyhow!("{} exited with {}", command, status));
+    }
+    if accepted.len() != vectors.len() {
+        return Err(anyhow!(
+            "{} answered {} lines for {} vectors",
+            command,
+            accepted.len(),
+            vectors.len()
+        ));
+    }
+    Ok(accepted)
+}
+
+/// Run the external verifier `command` over the test vectors, as library
+/// `name`
+pub fn run_external(name: &str, command: &str, args: &[String]) -> Result<ResultRow> {
+    Ok(ResultRow {
+        library: name.to_string(),
+        accepted: run_protocol(command, args, &generate_test_vectors())?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sh(script: &str) -> Vec<String> {
+        vec!["-c".to_string(), script.to_string()]
+    }
+
+    #[test]
+    fn test_run_protocol() {
+        let vec = generate_test_vectors();
+        // accepts the vectors whose signature ends with an even byte
+        let accepted = run_protocol(
+            "sh",
+            &sh("while read m p s; do case $s in *[02468ace]) echo 1;; *) echo 0;; esac; done"),
+            &vec,
+        )
+        .unwrap();
+        let expected: Vec<bool> = vec.iter().map(|tv| tv.signature[63] % 2 == 0).collect();
+        assert_eq!(accepted, expected);
+    }
+
+    #[test]
+    fn test_protocol_errors() {
+        let vec = generate_test_vectors();
+        // too few lines
+        assert!(run_protocol("sh", &sh("read l; echo 1"), &vec).is_err());
+        // too many lines, from a verifier that would otherwise never exit
+        assert!(run_protocol("sh", &sh("echo 1; while :; do echo 0; done"), &vec).is_err());
+        // invalid response
+        assert!(run_protocol("sh", &sh("while read l; do echo yes; done"), &vec).is_err());
+        // failure exit status
+        assert!(run_protocol("sh", &sh("while read l; do echo 1; done; exit 1"), &vec).is_err());
+        // missing executable
+        assert!(run_protocol("/nonexistent/verifier", &[], &vec).is_err());
+    }
+}
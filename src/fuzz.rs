@@ -0,0 +1,365 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Differential fuzzing of verifiers against the reference policies.
+//!
+//! Starting from the test vectors and an honest signature, we apply random
+//! structure-aware mutations to `(message, pub_key, signature)`: adding or
+//! substituting torsion points, flipping sign bits, adding multiples of L to
+//! S, and re-encoding points. Each verifier starts out consistent with every
+//! reference policy; a case narrows that set to the policies agreeing with
+//! the verifier on it. A case on which the verifier agrees with none of the
+//! policies still consistent is a disagreement no known policy difference
+//! explains, and is reported as such.
+
+use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT, scalar::Scalar};
+use rand::RngCore;
+use std::convert::TryInto;
+
+use crate::malleability::alternative_encodings;
+use crate::non_reducing_scalar52::Scalar52;
+use crate::policy::REFERENCE_POLICIES;
+use crate::signing::expand_seed;
+use crate::{
+    deserialize_point, generate_test_vectors, serialize_signature, EIGHT_TORSION,
+    EIGHT_TORSION_NON_CANONICAL,
+};
+
+/// How many unexplained disagreements we keep per verifier
+const MAX_REPORTED: usize = 10;
+
+#[derive(Clone, Debug)]
+pub struct FuzzCase {
+    pub message: Vec<u8>,
+    pub pub_key: [u8; 32],
+    pub signature: [u8; 64],
+    /// The mutations applied to the seed case, in order
+    pub mutations: Vec<String>,
+}
+
+/// A structure-aware mutation of a `FuzzCase`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mutation {
+    /// Add a torsion point to R
+    AddTorsionToR,
+    /// Add a torsion point to A
+    AddTorsionToA,
+    /// Replace R with a (possibly non-canonical) small order point
+    SmallR,
+    /// Replace A with a (possibly non-canonical) small order point
+    SmallA,
+    /// Flip the sign bit of R
+    FlipSignR,
+    /// Flip the sign bit of A
+    FlipSignA,
+    /// Add L to S, if the result fits in 256 bits
+    AddLToS,
+    /// Re-encode R as y + p, y - p or with a flipped sign bit, if possible
+    ReencodeR,
+    /// Re-encode A as y + p, y - p or with a flipped sign bit, if possible
+    ReencodeA,
+    /// Flip a bit of the message
+    FlipMessageBit,
+}
+
+impl Mutation {
+    pub const ALL: [Mutation; 10] = [
+        Mutation::AddTorsionToR,
+        Mutation::AddTorsionToA,
+        Mutation::SmallR,
+        Mutation::SmallA,
+        Mutation::FlipSignR,
+        Mutation::FlipSignA,
+        Mutation::AddLToS,
+        Mutation::ReencodeR,
+        Mutation::ReencodeA,
+        Mutation::FlipMessageBit,
+    ];
+}
+
+fn small_point(rng: &mut impl RngCore) -> [u8; 32] {
+    let idx = rng.next_u32() as usize % (EIGHT_TORSION.len() + EIGHT_TORSION_NON_CANONICAL.len());
+    if idx < EIGHT_TORSION.len() {
+        EIGHT_TORSION[idx]
+    } else {
+        EIGHT_TORSION_NON_CANONICAL[idx - EIGHT_TORSION.len()]
+    }
+}
+
+/// `bytes` plus a non-trivial torsion point, if `bytes` decodes
+fn add_torsion(rng: &mut impl RngCore, bytes: &[u8; 32]) -> Option<[u8; 32]> {
+    let point = deserialize_point(bytes).ok()?;
+    let torsion = deserialize_point(&EIGHT_TORSION[1 + rng.next_u32() as usize % 7]).unwrap();
+    Some((point + torsion).compress().to_bytes())
+}
+
+fn reencode(rng: &mut impl RngCore, bytes: &[u8; 32]) -> Option<[u8; 32]> {
+    let encodings = alternative_encodings(bytes);
+    if encodings.is_empty() {
+        None
+    } else {
+        Some(encodings[rng.next_u32() as usize % encodings.len()].0)
+    }
+}
+
+impl FuzzCase {
+    fn r(&self) -> [u8; 32] {
+        self.signature[..32].try_into().unwrap()
+    }
+
+    fn s(&self) -> [u8; 32] {
+        self.signature[32..].try_into().unwrap()
+    }
+
+    /// Apply `mutation`, returning whether it changed the case
+    pub fn mutate(&mut self, rng: &mut impl RngCore, mutation: Mutation) -> bool {
+        let (r, s) = (self.r(), self.s());
+        let (new_r, new_pub_key, new_s) = match mutation {
+            Mutation::AddTorsionToR => (add_torsion(rng, &r), None, None),
+            Mutation::AddTorsionToA => (None, add_torsion(rng, &self.pub_key), None),
+            Mutation::SmallR => (Some(small_point(rng)), None, None),
+            Mutation::SmallA => (None, Some(small_point(rng)), None),
+            Mutation::FlipSignR => {
+                let mut r = r;
+                r[31] ^= 0x80;
+                (Some(r), None, None)
+            }
+            Mutation::FlipSignA => {
+                let mut pub_key = self.pub_key;
+                pub_key[31] ^= 0x80;
+                (None, Some(pub_key), None)
+            }
+            Mutation::AddLToS => (
+                None,
+                None,
+                Scalar52::add_multiple_of_l(&Scalar52::from_bytes(&s), 1).map(|s| s.to_bytes()),
+            ),
+            Mutation::ReencodeR => (reencode(rng, &r), None, None),
+            Mutation::ReencodeA => (None, reencode(rng, &self.pub_key), None),
+            Mutation::FlipMessageBit => {
+                if self.message.is_empty() {
+                    return false;
+                }
+                let bit = rng.next_u32() as usize % (8 * self.message.len());
+                self.message[bit / 8] ^= 1 << (bit % 8);
+                self.mutations.push(format!("{:?}", mutation));
+                return true;
+            }
+        };
+        if new_r.is_none() && new_pub_key.is_none() && new_s.is_none() {
+            return false;
+        }
+        if let Some(new_r) = new_r {
+            self.signature[..32].copy_from_slice(&new_r);
+        }
+        if let Some(new_pub_key) = new_pub_key {
+            self.pub_key = new_pub_key;
+        }
+        if let Some(new_s) = new_s {
+            self.signature[32..].copy_from_slice(&new_s);
+        }
+        self.mutations.push(format!("{:?}", mutation));
+        true
+    }
+}
+
+/// The cases mutations start from: the test vectors, and an honest signature
+pub fn seed_cases(rng: &mut impl RngCore) -> Vec<FuzzCase> {
+    let mut cases: Vec<FuzzCase> = generate_test_vectors()
+        .into_iter()
+        .enumerate()
+        .map(|(i, tv)| FuzzCase {
+            message: tv.message.to_vec(),
+            pub_key: tv.pub_key,
+            signature: tv.signature[..].try_into().unwrap(),
+            mutations: vec![format!("vector #{}", i)],
+        })
+        .collect();
+
+    let mut seed = [0u8; 32];
+    rng.fill_bytes(&mut seed);
+    let mut message = [0u8; 32];
+    rng.fill_bytes(&mut message);
+    let expanded = expand_seed(&seed);
+    cases.push(FuzzCase {
+        message: message.to_vec(),
+        pub_key: expanded.public_key(),
+        signature: expanded.sign(&message)[..].try_into().unwrap(),
+        mutations: vec!["honest signature".to_string()],
+    });
+
+    // A signature with a mixed R, to give torsion mutations on R a chance
+    // to cancel out
+    let r = Scalar::from(7u64) * ED25519_BASEPOINT_POINT
+        + deserialize_point(&EIGHT_TORSION[4]).unwrap();
+    let signature = serialize_signature(&r, &Scalar::from(7u64));
+    cases.push(FuzzCase {
+        message: message.to_vec(),
+        pub_key: EIGHT_TORSION[0],
+        signature: signature[..].try_into().unwrap(),
+        mutations: vec!["mixed R, identity A".to_string()],
+    });
+    cases
+}
+
+/// A case on which a verifier agrees with no reference policy consistent
+/// with the cases before it
+#[derive(Clone, Debug)]
+pub struct Disagreement {
+    pub case: FuzzCase,
+    /// The verifier's verdict
+    pub accepted: bool,
+    /// The reference policies consistent with the verifier so far, all of
+    /// which returned the opposite verdict
+    pub consistent: Vec<&'static str>,
+}
+
+/// The outcome of a fuzzing run, for one verifier
+#[derive(Clone, Debug)]
+pub struct VerifierReport {
+    pub name: &'static str,
+    /// The reference policies agreeing with the verifier on every case but
+    /// the disagreements
+    pub consistent: Vec<&'static str>,
+    /// The number of unexplained disagreements
+    pub disagreement_count: usize,
+    /// The first unexplained disagreements
+    pub disagreements: Vec<Disagreement>,
+}
+
+impl VerifierReport {
+    pub fn describe(&self) -> String {
+        let mut out = format!(
+            "{}: consistent with [{}], {} unexplained disagreement(s)",
+            self.name,
+            self.consistent.join(", "),
+            self.disagreement_count
+        );
+        for d in self.disagreements.iter() {
+            out.push_str(&format!(
+                "\n  {} where [{}] {}, after {}:\n  \"message\": \"{}\", \"pub_key\": \"{}\", \"signature\": \"{}\"",
+                if d.accepted { "accepts" } else { "rejects" },
+                d.consistent.join(", "),
+                if d.accepted { "reject" } else { "accept" },
+                d.case.mutations.join(" > "),
+                hex::encode(&d.case.message),
+                hex::encode(&d.case.pub_key),
+                hex::encode(&d.case.signature[..])
+            ));
+        }
+        out
+    }
+}
+
+/// A verifier under test: its name, and whether it accepts
+/// `(message, pub_key, signature)`
+pub type Verifier<'a> = (&'static str, &'a dyn Fn(&[u8], &[u8], &[u8]) -> bool);
+
+/// Run `iterations` mutated cases, each derived from a random seed case by
+/// one to three mutations, through every verifier.
+pub fn fuzz(
+    rng: &mut impl RngCore,
+    iterations: usize,
+    verifiers: &[Verifier],
+) -> Vec<VerifierReport> {
+    let seeds = seed_cases(rng);
+    let mut reports: Vec<VerifierReport> = verifiers
+        .iter()
+        .map(|(name, _)| VerifierReport {
+            name,
+            consistent: REFERENCE_POLICIES.iter().map(|p| p.name).collect(),
+            disagreement_count: 0,
+            disagreements: Vec::new(),
+        })
+        .collect();
+
+    for _ in 0..iterations {
+        let mut case = seeds[rng.next_u32() as usize % seeds.len()].clone();
+        let rounds = 1 + rng.next_u32() % 3;
+        for _ in 0..rounds {
+            let mutation = Mutation::ALL[rng.next_u32() as usize % Mutation::ALL.len()];
+            case.mutate(rng, mutation);
+        }
+
+        let verdicts: Vec<(&'static str, bool)> = REFERENCE_POLICIES
+            .iter()
+            .map(|p| {
+                let accepted = p
+                    .verify(&case.message, &case.pub_key, &case.signature)
+                    .is_ok();
+                (p.name, accepted)
+            })
+            .collect();
+
+        for ((_, verify), report) in verifiers.iter().zip(reports.iter_mut()) {
+            let accepted = verify(&case.message, &case.pub_key, &case.signature);
+            let agreeing: Vec<&'static str> = report
+                .consistent
+                .iter()
+                .copied()
+                .filter(|name| verdicts.iter().any(|(n, v)| n == name && *v == accepted))
+                .collect();
+            if agreeing.is_empty() {
+                debug!(
+                    "{} {} after {}",
+                    report.name,
+                    if accepted { "accepts" } else { "rejects" },
+                    case.mutations.join(" > ")
+                );
+                report.disagreement_count += 1;
+                if report.disagreements.len() < MAX_REPORTED {
+                    report.disagreements.push(Disagreement {
+                        case: case.clone(),
+                        accepted,
+                        consistent: report.consistent.clone(),
+                    });
+                }
+            } else {
+                report.consistent = agreeing;
+            }
+        }
+    }
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::new_rng;
+    use crate::policy;
+
+    #[test]
+    fn test_fuzz_reference_policies() {
+        // every reference policy is trivially explained by itself
+        let closures: Vec<_> = REFERENCE_POLICIES
+            .iter()
+            .map(|p| move |m: &[u8], pk: &[u8], sig: &[u8]| p.verify(m, pk, sig).is_ok())
+            .collect();
+        let verifiers: Vec<Verifier> = REFERENCE_POLICIES
+            .iter()
+            .zip(closures.iter())
+            .map(|(p, verify)| -> Verifier { (p.name, verify) })
+            .collect();
+        let reports = fuzz(&mut new_rng(), 500, &verifiers);
+        for report in reports.iter() {
+            assert_eq!(report.disagreement_count, 0, "{}", report.describe());
+            assert!(report.consistent.contains(&report.name));
+        }
+    }
+
+    #[test]
+    fn test_fuzz_unexplained() {
+        // a verifier accepting everything disagrees with every policy on
+        // mutations invalidating the signature
+        let accept_all = |_: &[u8], _: &[u8], _: &[u8]| true;
+        let reports = fuzz(&mut new_rng(), 200, &[("accept all", &accept_all)]);
+        assert!(reports[0].disagreement_count > 0);
+
+        let cofactored =
+            |m: &[u8], pk: &[u8], sig: &[u8]| policy::COFACTORED.verify(m, pk, sig).is_ok();
+        let reports = fuzz(&mut new_rng(), 200, &[("cofactored", &cofactored)]);
+        assert_eq!(reports[0].consistent, vec!["cofactored"]);
+    }
+}
@@ -0,0 +1,311 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! The complete grid of A order × R order × S class vectors.
+//!
+//! `A = [a]B + T_A` and `R = [r]B + T_R`, where the prime-order component is
+//! zero for identity and small points, and the torsion component is zero for
+//! identity and prime-order points. S is then chosen as `r + k * a`, which
+//! satisfies the cofactored equation, and the message ground so that
+//! `T_R + [k]T_A = 0` whenever possible, which also satisfies the
+//! cofactorless one. Some cells cannot satisfy either (e.g. S = 0 with a
+//! prime-order component in R): they are still generated, and labeled with the
+//! policies accepting them, i.e. none.
+
+use core::fmt;
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_POINT, edwards::EdwardsPoint, scalar::Scalar, traits::IsIdentity,
+};
+use rand::RngCore;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::non_reducing_scalar52::Scalar52;
+use crate::policy::REFERENCE_POLICIES;
+use crate::{compute_hram, deserialize_point, new_rng, serialize_signature, EIGHT_TORSION};
+
+/// How many messages we try to satisfy the cofactorless equation
+const MAX_GRIND: usize = 256;
+
+/// The order of a point, as it matters for verification
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointClass {
+    Identity,
+    /// A non-trivial point of order dividing 8
+    Small,
+    /// A prime-order point plus a non-trivial torsion point
+    Mixed,
+    /// A point of order L
+    Prime,
+}
+
+impl PointClass {
+    pub const ALL: [PointClass; 4] = [
+        PointClass::Identity,
+        PointClass::Small,
+        PointClass::Mixed,
+        PointClass::Prime,
+    ];
+
+    /// The class of `point`
+    pub fn of(point: &EdwardsPoint) -> PointClass {
+        match (point.is_small_order(), point.is_torsion_free()) {
+            (true, true) => PointClass::Identity,
+            (true, false) => PointClass::Small,
+            (false, false) => PointClass::Mixed,
+            (false, true) => PointClass::Prime,
+        }
+    }
+
+    fn has_prime_component(self) -> bool {
+        self == PointClass::Mixed || self == PointClass::Prime
+    }
+
+    fn has_torsion_component(self) -> bool {
+        self == PointClass::Small || self == PointClass::Mixed
+    }
+}
+
+impl fmt::Display for PointClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PointClass::Identity => write!(f, "identity"),
+            PointClass::Small => write!(f, "small"),
+            PointClass::Mixed => write!(f, "mixed"),
+            PointClass::Prime => write!(f, "prime-order"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScalarClass {
+    Zero,
+    /// 0 < S < L
+    Reduced,
+    /// L ≤ S < 2^256
+    Unreduced,
+}
+
+impl ScalarClass {
+    pub const ALL: [ScalarClass; 3] = [
+        ScalarClass::Zero,
+        ScalarClass::Reduced,
+        ScalarClass::Unreduced,
+    ];
+
+    /// The class of the 256-bit little-endian integer `s`
+    pub fn of(s: &[u8; 32]) -> ScalarClass {
+        let s = Scalar52::from_bytes(s);
+        if s == Scalar52::zero() {
+            ScalarClass::Zero
+        } else if s.is_canonical() {
+            ScalarClass::Reduced
+        } else {
+            ScalarClass::Unreduced
+        }
+    }
+}
+
+impl fmt::Display for ScalarClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScalarClass::Zero => write!(f, "S = 0"),
+            ScalarClass::Reduced => write!(f, "0 < S < L"),
+            ScalarClass::Unreduced => write!(f, "S ≥ L"),
+        }
+    }
+}
+
+pub struct GridVector {
+    pub a_class: PointClass,
+    pub r_class: PointClass,
+    pub s_class: ScalarClass,
+    pub message: [u8; 32],
+    pub pub_key: [u8; 32],
+    pub signature: Vec<u8>,
+    pub accepted_by: Vec<&'static str>,
+}
+
+impl GridVector {
+    pub fn comment(&self) -> String {
+        format!("A {}, R {}, {}", self.a_class, self.r_class, self.s_class)
+    }
+}
+
+impl Serialize for GridVector {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("GridVector", 5)?;
+        state.serialize_field("comment", &self.comment())?;
+        state.serialize_field("message", &hex::encode(&self.message))?;
+        state.serialize_field("pub_key", &hex::encode(&self.pub_key))?;
+        state.serialize_field("signature", &hex::encode(&self.signature))?;
+        state.serialize_field("accepted_by", &self.accepted_by)?;
+        state.end()
+    }
+}
+
+pub fn random_nonzero_scalar(rng: &mut impl RngCore) -> Scalar {
+    loop {
+        let mut scalar_bytes = [0u8; 32];
+        rng.fill_bytes(&mut scalar_bytes);
+        let scalar = Scalar::from_bytes_mod_order(scalar_bytes);
+        if scalar != Scalar::zero() {
+            return scalar;
+        }
+    }
+}
+
+/// A point of `class`, with its prime-order discrete log
+fn point(rng: &mut impl RngCore, class: PointClass) -> (EdwardsPoint, Scalar) {
+    let scalar = if class.has_prime_component() {
+        random_nonzero_scalar(rng)
+    } else {
+        Scalar::zero()
+    };
+    let mut point = scalar * ED25519_BASEPOINT_POINT;
+    if class.has_torsion_component() {
+        // a generator of E[8], so that any T_R is a multiple of T_A
+        point += deserialize_point(&EIGHT_TORSION[1]).unwrap();
+    }
+    (point, scalar)
+}
+
+fn grid_vector(
+    rng: &mut impl RngCore,
+    a_class: PointClass,
+    r_class: PointClass,
+    s_class: ScalarClass,
+) -> GridVector {
+    let (a_point, a) = point(rng, a_class);
+    let (r_point, r) = point(rng, r_class);
+    debug_assert_eq!(PointClass::of(&a_point), a_class);
+    debug_assert_eq!(PointClass::of(&r_point), r_class);
+
+    // S = r + k * a satisfies [8]R + [8][k]A = [8][S]B; grind the message
+    // so that the torsion components cancel out as well
+    let mut message = [0u8; 32];
+    let mut k = Scalar::zero();
+    for _ in 0..MAX_GRIND {
+        rng.fill_bytes(&mut message);
+        k = compute_hram(&message, &a_point, &r_point);
+        if (r_point + k * a_point - (r + k * a) * ED25519_BASEPOINT_POINT).is_identity() {
+            break;
+        }
+    }
+
+    let s = r + k * a;
+    let s_bytes = match s_class {
+        ScalarClass::Zero => [0u8; 32],
+        // if r + k * a = 0, no valid signature has a non-zero S
+        ScalarClass::Reduced if s == Scalar::zero() => random_nonzero_scalar(rng).to_bytes(),
+        ScalarClass::Reduced => s.to_bytes(),
+        ScalarClass::Unreduced => {
+            Scalar52::add_multiple_of_l(&Scalar52::from_bytes(&s.to_bytes()), 1)
+                .unwrap()
+                .to_bytes()
+        }
+    };
+    debug_assert_eq!(ScalarClass::of(&s_bytes), s_class);
+
+    let pub_key = a_point.compress().to_bytes();
+    let mut signature = serialize_signature(&r_point, &Scalar::zero());
+    signature[32..].copy_from_slice(&s_bytes);
+    let accepted_by = REFERENCE_POLICIES
+        .iter()
+        .filter(|p| p.verify(&message, &pub_key, &signature).is_ok())
+        .map(|p| p.name)
+        .collect();
+    GridVector {
+        a_class,
+        r_class,
+        s_class,
+        message,
+        pub_key,
+        signature,
+        accepted_by,
+    }
+}
+
+/// Every combination of A order, R order and S class
+pub fn grid_vectors() -> Vec<GridVector> {
+    let mut rng = new_rng();
+    let mut vec = Vec::new();
+    for a_class in PointClass::ALL.iter() {
+        for r_class in PointClass::ALL.iter() {
+            for s_class in ScalarClass::ALL.iter() {
+                let tv = grid_vector(&mut rng, *a_class, *r_class, *s_class);
+                debug!(
+                    "{}\n\
+                     accepted by: {}\n\
+                     \"message\": \"{}\", \"pub_key\": \"{}\", \"signature\": \"{}\"",
+                    tv.comment(),
+                    tv.accepted_by.join(", "),
+                    hex::encode(&tv.message),
+                    hex::encode(&tv.pub_key),
+                    hex::encode(&tv.signature)
+                );
+                vec.push(tv);
+            }
+        }
+    }
+    vec
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryInto;
+
+    #[test]
+    fn test_grid_vectors() {
+        let vec = grid_vectors();
+        assert_eq!(vec.len(), 48);
+        for tv in vec.iter() {
+            let accepted = |name: &str| tv.accepted_by.contains(&name);
+            let comment = tv.comment();
+            // r + k * a is non-zero as soon as R or A has a prime component
+            let nonzero = tv.a_class.has_prime_component() || tv.r_class.has_prime_component();
+            let satisfiable = match tv.s_class {
+                ScalarClass::Zero => !nonzero,
+                ScalarClass::Reduced => nonzero,
+                // S = (r + k * a) + L, even when r + k * a = 0
+                ScalarClass::Unreduced => true,
+            };
+            // the cofactored equation holds exactly for satisfiable cells
+            assert_eq!(accepted("cofactored"), satisfiable, "{}", comment);
+            // the cofactorless one needs torsion components that can cancel out
+            if accepted("cofactorless") {
+                assert!(satisfiable, "{}", comment);
+                assert!(
+                    !tv.r_class.has_torsion_component() || tv.a_class.has_torsion_component(),
+                    "{}",
+                    comment
+                );
+            }
+            if tv.s_class == ScalarClass::Unreduced {
+                assert!(!accepted("RFC 8032") && !accepted("ZIP-215"), "{}", comment);
+            }
+            // strict only rejects points of small order, mixed ones pass
+            if !tv.a_class.has_prime_component() || !tv.r_class.has_prime_component() {
+                assert!(!accepted("strict"), "{}", comment);
+            }
+        }
+        for tv in vec.iter() {
+            let pub_key = deserialize_point(&tv.pub_key).unwrap();
+            let r = deserialize_point(&tv.signature[..32]).unwrap();
+            assert_eq!(PointClass::of(&pub_key), tv.a_class);
+            assert_eq!(PointClass::of(&r), tv.r_class);
+            assert_eq!(
+                ScalarClass::of(&tv.signature[32..].try_into().unwrap()),
+                tv.s_class
+            );
+        }
+        // an honest signature
+        assert_eq!(vec[46].comment(), "A prime-order, R prime-order, 0 < S < L");
+        assert_eq!(vec[46].accepted_by.len(), REFERENCE_POLICIES.len());
+    }
+}
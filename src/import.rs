@@ -0,0 +1,345 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Loaders for external vector sets, annotated with our classification.
+//!
+//! RFC 8032 §7.1 vectors are read from the text of the RFC (`-----TEST`
+//! blocks, with hex wrapped over several lines and possibly page breaks), and
+//! Wycheproof vectors from `eddsa_test.json` (or `ed25519_test.json`). Each
+//! vector is converted to a `TestVector`, and annotated with the classes of A,
+//! R and S, the canonicity of A and R, their torsion components, and the
+//! reference policies accepting it.
+
+use anyhow::{anyhow, Context, Result};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde::Deserialize;
+use std::convert::TryInto;
+
+use crate::analyze::{analyze, Analysis};
+use crate::grid::PointClass;
+use crate::{deserialize_point, TestVector};
+
+/// The expected result given by the source
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Expected {
+    Valid,
+    Invalid,
+    /// Wycheproof's "acceptable": either result is fine
+    Acceptable,
+}
+
+pub struct ImportedVector {
+    /// e.g. `RFC 8032 TEST 1` or `Wycheproof tcId 12`
+    pub source: String,
+    pub comment: String,
+    pub flags: Vec<String>,
+    pub expected: Expected,
+    pub vector: TestVector,
+}
+
+impl Serialize for ImportedVector {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("ImportedVector", 16)?;
+        state.serialize_field("source", &self.source)?;
+        state.serialize_field("comment", &self.comment)?;
+        state.serialize_field("flags", &self.flags)?;
+        state.serialize_field(
+            "expected",
+            match self.expected {
+                Expected::Valid => "valid",
+                Expected::Invalid => "invalid",
+                Expected::Acceptable => "acceptable",
+            },
+        )?;
+        state.serialize_field("message", &hex::encode(&self.vector.message))?;
+        state.serialize_field("pub_key", &hex::encode(&self.vector.pub_key))?;
+        state.serialize_field("signature", &hex::encode(&self.vector.signature))?;
+
+        // a signature of the wrong length gets no classification
+        let analysis = analyze(
+            &self.vector.message,
+            &self.vector.pub_key,
+            &self.vector.signature,
+        )
+        .ok();
+        for (class_field, canonical_field, torsion_field, report) in [
+            (
+                "a_class",
+                "a_canonical",
+                "a_torsion_index",
+                analysis.as_ref().map(|a| &a.pub_key),
+            ),
+            (
+                "r_class",
+                "r_canonical",
+                "r_torsion_index",
+                analysis.as_ref().map(|a| &a.r),
+            ),
+        ]
+        .iter()
+        {
+            let decoded = report.and_then(|report| report.decoded.as_ref());
+            let class = decoded.map(|_| {
+                PointClass::of(&deserialize_point(&report.unwrap().encoding).unwrap()).to_string()
+            });
+            state.serialize_field(class_field, &class)?;
+            state.serialize_field(canonical_field, &report.map(|report| report.is_canonical()))?;
+            state.serialize_field(torsion_field, &decoded.map(|decoded| decoded.torsion_index))?;
+        }
+        state.serialize_field("s_class", &analysis.as_ref().map(|a| a.s_class.to_string()))?;
+        state.serialize_field(
+            "accepted_by",
+            &analysis
+                .as_ref()
+                .map(Analysis::accepted_by)
+                .unwrap_or_default(),
+        )?;
+        state.end()
+    }
+}
+
+fn decode_pub_key(hex_pub_key: &str) -> Result<[u8; 32]> {
+    hex::decode(hex_pub_key)?
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow!("public key {} is not 32 bytes", hex_pub_key))
+}
+
+/// Parse the Ed25519 test vectors of RFC 8032 §7.1, from the text of the RFC
+/// or an excerpt of it
+pub fn load_rfc8032(text: &str) -> Result<Vec<ImportedVector>> {
+    let mut vec = Vec::new();
+    for block in text.split("-----").skip(1) {
+        let mut lines = block.lines();
+        let name = lines.next().unwrap_or_default().trim().to_string();
+        let mut algorithm = String::new();
+        let mut field = "";
+        let mut fields: Vec<(&str, String)> = Vec::new();
+        for line in lines.map(str::trim) {
+            if line.ends_with(':') {
+                field = match line {
+                    "ALGORITHM:" => "algorithm",
+                    "SECRET KEY:" => "secret key",
+                    "PUBLIC KEY:" => "public key",
+                    "SIGNATURE:" => "signature",
+                    _ if line.starts_with("MESSAGE") => "message",
+                    _ => "",
+                };
+                fields.push((field, String::new()));
+            } else if field == "algorithm" && !line.is_empty() {
+                algorithm = line.to_string();
+            } else if !line.is_empty() && line.chars().all(|c| c.is_ascii_hexdigit()) {
+                // page headers and footers are not hex, and the prose after the
+                // last signature of a section only follows a complete one
+                match fields.last_mut() {
+                    Some(("signature", value)) if value.len() >= 128 => (),
+                    Some((_, value)) => value.push_str(line),
+                    None => (),
+                }
+            }
+        }
+        // §7.2 to §7.5 cover Ed25519ctx, Ed25519ph and Ed448
+        if algorithm != "Ed25519" {
+            continue;
+        }
+        let get = |wanted: &str| {
+            fields
+                .iter()
+                .find(|(field, _)| *field == wanted)
+                .map(|(_, value)| value.as_str())
+                .ok_or_else(|| anyhow!("{}: no {}", name, wanted))
+        };
+        vec.push(ImportedVector {
+            source: format!("RFC 8032 {}", name),
+            comment: String::new(),
+            flags: Vec::new(),
+            expected: Expected::Valid,
+            vector: TestVector {
+                message: hex::decode(get("message")?).with_context(|| name.clone())?,
+                pub_key: decode_pub_key(get("public key")?).with_context(|| name.clone())?,
+                signature: hex::decode(get("signature")?).with_context(|| name.clone())?,
+            },
+        });
+    }
+    if vec.is_empty() {
+        return Err(anyhow!("no RFC 8032 test vectors found"));
+    }
+    Ok(vec)
+}
+
+#[derive(Deserialize)]
+struct WycheproofFile {
+    #[serde(rename = "testGroups")]
+    test_groups: Vec<WycheproofGroup>,
+}
+
+#[derive(Deserialize)]
+struct WycheproofKey {
+    pk: String,
+}
+
+#[derive(Deserialize)]
+struct WycheproofGroup {
+    /// `eddsa_test.json` names the key `key`, later files `publicKey`
+    #[serde(alias = "publicKey")]
+    key: WycheproofKey,
+    tests: Vec<WycheproofTest>,
+}
+
+#[derive(Deserialize)]
+struct WycheproofTest {
+    #[serde(rename = "tcId")]
+    tc_id: u64,
+    #[serde(default)]
+    comment: String,
+    msg: String,
+    sig: String,
+    result: String,
+    #[serde(default)]
+    flags: Vec<String>,
+}
+
+/// Parse a Wycheproof EdDSA verification file such as `eddsa_test.json`
+pub fn load_wycheproof(json: &str) -> Result<Vec<ImportedVector>> {
+    let file: WycheproofFile = serde_json::from_str(json)?;
+    let mut vec = Vec::new();
+    for group in file.test_groups {
+        let pub_key = decode_pub_key(&group.key.pk)?;
+        for test in group.tests {
+            let context = || format!("tcId {}", test.tc_id);
+            vec.push(ImportedVector {
+                source: format!("Wycheproof tcId {}", test.tc_id),
+                expected: match test.result.as_str() {
+                    "valid" => Expected::Valid,
+                    "invalid" => Expected::Invalid,
+                    "acceptable" => Expected::Acceptable,
+                    other => return Err(anyhow!("tcId {}: unknown result {}", test.tc_id, other)),
+                },
+                vector: TestVector {
+                    message: hex::decode(&test.msg).with_context(context)?,
+                    pub_key,
+                    signature: hex::decode(&test.sig).with_context(context)?,
+                },
+                comment: test.comment,
+                flags: test.flags,
+            });
+        }
+    }
+    Ok(vec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::signing_vectors;
+
+    /// The reference policies accepting `imported`
+    fn accepted_by(imported: &ImportedVector) -> Vec<&'static str> {
+        analyze(
+            &imported.vector.message,
+            &imported.vector.pub_key,
+            &imported.vector.signature,
+        )
+        .map(|analysis| analysis.accepted_by())
+        .unwrap_or_default()
+    }
+
+    /// `hex` wrapped as in the RFC
+    fn wrap(hex: &str) -> String {
+        hex.as_bytes()
+            .chunks(32)
+            .map(|chunk| format!("   {}\n", std::str::from_utf8(chunk).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_load_rfc8032() {
+        let rfc_vectors = &signing_vectors()[..5];
+        let mut text = String::from("7.1.  Test Vectors for Ed25519\n\n");
+        for (idx, tv) in rfc_vectors.iter().enumerate() {
+            text.push_str(&format!(
+                "   -----{}\n\n   ALGORITHM:\n   Ed25519\n\n   SECRET KEY:\n{}\n   PUBLIC KEY:\n{}\n   MESSAGE (length {} bytes):\n{}\n",
+                tv.comment,
+                wrap(&hex::encode(&tv.seed)),
+                wrap(&hex::encode(&tv.pub_key)),
+                tv.message.len(),
+                wrap(&hex::encode(&tv.message)),
+            ));
+            if idx == 2 {
+                // a page break in the middle of the signature
+                let signature = hex::encode(&tv.signature);
+                text.push_str(&format!(
+                    "   SIGNATURE:\n{}\nJosefsson & Liusvaara         Informational                    [Page 25]\n\u{c}\nRFC 8032                EdDSA: Ed25519 and Ed448            January 2017\n\n{}\n",
+                    wrap(&signature[..64]),
+                    wrap(&signature[64..])
+                ));
+            } else {
+                text.push_str(&format!(
+                    "   SIGNATURE:\n{}\n",
+                    wrap(&hex::encode(&tv.signature))
+                ));
+            }
+        }
+
+        let vec = load_rfc8032(&text).unwrap();
+        assert_eq!(vec.len(), rfc_vectors.len());
+        for (imported, tv) in vec.iter().zip(rfc_vectors.iter()) {
+            assert_eq!(imported.source, format!("RFC 8032 {}", tv.comment));
+            assert_eq!(imported.vector.message, tv.message);
+            assert_eq!(imported.vector.pub_key, tv.pub_key);
+            assert_eq!(imported.vector.signature, tv.signature);
+            // honest signatures pass every policy
+            assert_eq!(accepted_by(imported).len(), 8, "{}", imported.source);
+            let json = serde_json::to_value(imported).unwrap();
+            assert_eq!(json["a_class"], "prime-order");
+            assert_eq!(json["s_class"], "0 < S < L");
+        }
+        assert!(load_rfc8032("no vectors").is_err());
+        let ctx = text.replace("Ed25519\n", "Ed25519ctx\n");
+        assert!(load_rfc8032(&ctx).is_err());
+    }
+
+    #[test]
+    fn test_load_wycheproof() {
+        let tv = &signing_vectors()[0];
+        let mut truncated = tv.signature.clone();
+        truncated.pop();
+        let json = format!(
+            r#"{{"algorithm": "EDDSA", "testGroups": [
+                {{"key": {{"curve": "edwards25519", "pk": "{pk}"}}, "type": "EddsaVerify", "tests": [
+                    {{"tcId": 1, "comment": "", "msg": "", "sig": "{sig}", "result": "valid", "flags": []}},
+                    {{"tcId": 2, "comment": "truncated", "msg": "", "sig": "{truncated}", "result": "invalid"}}
+                ]}},
+                {{"publicKey": {{"pk": "{small}"}}, "tests": [
+                    {{"tcId": 3, "comment": "small order key", "msg": "", "sig": "{small_sig}", "result": "acceptable", "flags": ["SmallOrder"]}}
+                ]}}
+            ]}}"#,
+            pk = hex::encode(&tv.pub_key),
+            sig = hex::encode(&tv.signature),
+            truncated = hex::encode(&truncated),
+            small = hex::encode(&crate::EIGHT_TORSION[2]),
+            small_sig = hex::encode([[0u8; 32], [0u8; 32]].concat()),
+        );
+        let vec = load_wycheproof(&json).unwrap();
+        assert_eq!(vec.len(), 3);
+        assert_eq!(vec[0].expected, Expected::Valid);
+        assert_eq!(accepted_by(&vec[0]).len(), 8);
+
+        assert_eq!(vec[1].expected, Expected::Invalid);
+        assert!(accepted_by(&vec[1]).is_empty());
+        assert!(serde_json::to_value(&vec[1]).unwrap()["s_class"].is_null());
+
+        // a small order A and R, with S = 0
+        assert_eq!(vec[2].expected, Expected::Acceptable);
+        assert_eq!(vec[2].flags, vec!["SmallOrder".to_string()]);
+        let json = serde_json::to_value(&vec[2]).unwrap();
+        assert_eq!(json["a_class"], "small");
+        assert_eq!(json["a_torsion_index"], 2);
+        assert_eq!(json["s_class"], "S = 0");
+    }
+}
@@ -0,0 +1,292 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Decode and classify arbitrary 32-byte Edwards point encodings.
+
+use anyhow::Result;
+use core::fmt;
+use curve25519_dalek::{edwards::EdwardsPoint, scalar::Scalar, traits::IsIdentity};
+use num_bigint::BigUint;
+
+use crate::{check_slice_size, deserialize_point, EIGHT_TORSION, EIGHT_TORSION_NON_CANONICAL};
+
+/// p = 2^255 - 19
+fn field_modulus() -> BigUint {
+    (BigUint::from(1u8) << 255) - BigUint::from(19u8)
+}
+
+/// The inverse of `a` mod p
+fn invert(a: &BigUint) -> BigUint {
+    let p = field_modulus();
+    a.modpow(&(&p - BigUint::from(2u8)), &p)
+}
+
+/// The affine x coordinate of the point with affine y coordinate `y`, with
+/// its parity given by `sign`, or `None` if there is no such point.
+fn recover_x(y: &BigUint, sign: bool) -> Option<BigUint> {
+    let p = field_modulus();
+    let one = BigUint::from(1u8);
+    // d = -121665 / 121666
+    let d = (&p - BigUint::from(121_665u32)) * invert(&BigUint::from(121_666u32)) % &p;
+    let y2 = y * y % &p;
+    // x^2 = (y^2 - 1) / (d y^2 + 1)
+    let x2 = (&y2 + &p - &one) * invert(&((&d * &y2 + &one) % &p)) % &p;
+
+    // p = 5 mod 8: x = x2^((p + 3) / 8), times sqrt(-1) if that squares to -x2
+    let mut x = x2.modpow(&((&p + BigUint::from(3u8)) >> 3), &p);
+    if &x * &x % &p != x2 {
+        let sqrt_m1 = BigUint::from(2u8).modpow(&((&p - &one) >> 2), &p);
+        x = x * sqrt_m1 % &p;
+    }
+    if &x * &x % &p != x2 {
+        return None;
+    }
+    if x.bit(0) != sign {
+        x = (&p - x) % &p;
+    }
+    Some(x)
+}
+
+/// Split `point` as `Q + T`, with Q of order 1 or L and T in E[8], and return
+/// Q with the index of T in `EIGHT_TORSION`.
+///
+/// Cofactored verification only checks the prime-order components of R, A
+/// and `[S]B - R - [k]A`; cofactorless verification additionally needs
+/// `T_R + [k]T_A` to be the identity, which is where the two disagree.
+pub fn decompose(point: &EdwardsPoint) -> (EdwardsPoint, usize) {
+    // [L - 1]P + P = [L]P = [L]T, and T = [5L]T as 5L = 1 mod 8
+    let l_point = (Scalar::zero() - Scalar::one()) * point + point;
+    let torsion = Scalar::from(5u8) * l_point;
+    let torsion_index = EIGHT_TORSION
+        .iter()
+        .position(|t| *t == torsion.compress().to_bytes())
+        .expect("the torsion component is in E[8]");
+    (point - torsion, torsion_index)
+}
+
+/// What we know of a point that decodes
+pub struct DecodedPoint {
+    /// The sign bit is set for x = 0
+    pub negative_zero: bool,
+    pub canonical_encoding: [u8; 32],
+    pub x: BigUint,
+    pub y: BigUint,
+    /// The order of the torsion component: 1, 2, 4 or 8
+    pub torsion_order: u8,
+    /// The order is `torsion_order * L` rather than `torsion_order`
+    pub has_prime_component: bool,
+    /// The index of the torsion component in `EIGHT_TORSION`
+    pub torsion_index: usize,
+}
+
+pub struct PointReport {
+    pub encoding: [u8; 32],
+    /// The 255 low bits of the encoding, y as sent, are below p
+    pub y_below_p: bool,
+    /// `None` if the encoding does not decode to a curve point
+    pub decoded: Option<DecodedPoint>,
+    /// The index of the encoding in `EIGHT_TORSION_NON_CANONICAL`
+    pub non_canonical_torsion_index: Option<usize>,
+}
+
+impl PointReport {
+    /// The encoding is the one produced by `compress`
+    pub fn is_canonical(&self) -> bool {
+        self.decoded
+            .as_ref()
+            .map(|decoded| decoded.canonical_encoding == self.encoding)
+            .unwrap_or(false)
+    }
+
+    /// The order, as `1` to `8`, `L` or a multiple of `L`
+    pub fn order(&self) -> Option<String> {
+        self.decoded.as_ref().map(|decoded| {
+            match (decoded.torsion_order, decoded.has_prime_component) {
+                (order, false) => order.to_string(),
+                (1, true) => "L".to_string(),
+                (order, true) => format!("{}L", order),
+            }
+        })
+    }
+}
+
+/// Decode `bytes` and classify the point
+pub fn inspect_point(bytes: &[u8]) -> Result<PointReport> {
+    let mut encoding = [0u8; 32];
+    encoding.copy_from_slice(check_slice_size(bytes, 32, "point")?);
+    let mut y_bytes = encoding;
+    y_bytes[31] &= 0x7f;
+    let y_below_p = BigUint::from_bytes_le(&y_bytes) < field_modulus();
+    let non_canonical_torsion_index = EIGHT_TORSION_NON_CANONICAL
+        .iter()
+        .position(|torsion| *torsion == encoding);
+
+    let decoded = deserialize_point(&encoding).ok().map(|point| {
+        let canonical_encoding = point.compress().to_bytes();
+        let mut y = canonical_encoding;
+        y[31] &= 0x7f;
+        let y = BigUint::from_bytes_le(&y);
+        let x = recover_x(&y, canonical_encoding[31] & 0x80 != 0)
+            .expect("a decoded point has an x coordinate");
+
+        let (prime_component, torsion_index) = decompose(&point);
+        let torsion = deserialize_point(&EIGHT_TORSION[torsion_index]).unwrap();
+        let torsion_order = [1u8, 2, 4, 8]
+            .iter()
+            .copied()
+            .find(|order| (Scalar::from(*order) * torsion).is_identity())
+            .unwrap();
+        DecodedPoint {
+            negative_zero: x == BigUint::from(0u8) && encoding[31] & 0x80 != 0,
+            canonical_encoding,
+            x,
+            y,
+            torsion_order,
+            has_prime_component: !prime_component.is_identity(),
+            torsion_index,
+        }
+    });
+
+    Ok(PointReport {
+        encoding,
+        y_below_p,
+        decoded,
+        non_canonical_torsion_index,
+    })
+}
+
+impl fmt::Display for PointReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "encoding:            {}", hex::encode(&self.encoding))?;
+        writeln!(f, "y < p:               {}", self.y_below_p)?;
+        let decoded = match &self.decoded {
+            Some(decoded) => decoded,
+            None => return write!(f, "decodes:             false"),
+        };
+        writeln!(f, "decodes:             true")?;
+        writeln!(
+            f,
+            "canonical:           {}{}",
+            self.is_canonical(),
+            if decoded.negative_zero {
+                " (sign bit set for x = 0)"
+            } else {
+                ""
+            }
+        )?;
+        writeln!(
+            f,
+            "canonical encoding:  {}",
+            hex::encode(&decoded.canonical_encoding)
+        )?;
+        writeln!(f, "x:                   {}", decoded.x)?;
+        writeln!(f, "y:                   {}", decoded.y)?;
+        writeln!(f, "order:               {}", self.order().unwrap())?;
+        writeln!(
+            f,
+            "torsion component:   EIGHT_TORSION[{}] (order {})",
+            decoded.torsion_index, decoded.torsion_order
+        )?;
+        match self.non_canonical_torsion_index {
+            Some(idx) => write!(
+                f,
+                "non-canonical entry: EIGHT_TORSION_NON_CANONICAL[{}]",
+                idx
+            ),
+            None => write!(f, "non-canonical entry: none"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{compute_hram, deserialize_signature, verify_cofactored, verify_cofactorless};
+    use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT, traits::Identity};
+    use rand::RngCore;
+
+    #[test]
+    fn test_inspect_torsion_points() {
+        let orders = ["1", "8", "4", "8", "2", "8", "4", "8"];
+        for (idx, torsion) in EIGHT_TORSION.iter().enumerate() {
+            let report = inspect_point(torsion).unwrap();
+            assert!(report.is_canonical());
+            assert_eq!(report.order().unwrap(), orders[idx]);
+            let decoded = report.decoded.unwrap();
+            assert_eq!(decoded.torsion_index, idx);
+            assert!(!decoded.has_prime_component);
+        }
+
+        for (idx, torsion) in EIGHT_TORSION_NON_CANONICAL.iter().enumerate() {
+            let report = inspect_point(torsion).unwrap();
+            assert!(!report.is_canonical(), "{}", idx);
+            assert_eq!(report.non_canonical_torsion_index, Some(idx));
+            // a sign bit set for x = 0, or y ≥ p
+            let decoded = report.decoded.as_ref().unwrap();
+            assert!(decoded.negative_zero || !report.y_below_p, "{}", idx);
+        }
+    }
+
+    #[test]
+    fn test_decompose() {
+        let mut rng = crate::new_rng();
+        let mut scalar_bytes = [0u8; 32];
+        rng.fill_bytes(&mut scalar_bytes);
+        let prime = Scalar::from_bytes_mod_order(scalar_bytes) * ED25519_BASEPOINT_POINT;
+        for (idx, torsion) in EIGHT_TORSION.iter().enumerate() {
+            let torsion = deserialize_point(torsion).unwrap();
+            assert_eq!(decompose(&(prime + torsion)), (prime, idx));
+            assert_eq!(decompose(&torsion), (EdwardsPoint::identity(), idx));
+        }
+    }
+
+    #[test]
+    fn test_decompose_explains_cofactorless_failures() {
+        for tv in crate::generate_test_vectors().iter() {
+            let (pub_key, (r, s)) = match (
+                deserialize_point(&tv.pub_key),
+                deserialize_signature(&tv.signature),
+            ) {
+                (Ok(pub_key), Ok(signature)) => (pub_key, signature),
+                _ => continue,
+            };
+            let k = compute_hram(&tv.message, &pub_key, &r);
+            let t_r = deserialize_point(&EIGHT_TORSION[decompose(&r).1]).unwrap();
+            let t_a = deserialize_point(&EIGHT_TORSION[decompose(&pub_key).1]).unwrap();
+            let cofactored = verify_cofactored(&tv.message, &pub_key, &(r, s)).is_ok();
+            let cofactorless = verify_cofactorless(&tv.message, &pub_key, &(r, s)).is_ok();
+            assert_eq!(cofactorless, cofactored && (t_r + k * t_a).is_identity());
+        }
+    }
+
+    #[test]
+    fn test_inspect_mixed_point() {
+        let basepoint = ED25519_BASEPOINT_POINT.compress().to_bytes();
+        let report = inspect_point(&basepoint).unwrap();
+        assert_eq!(report.order().unwrap(), "L");
+        // the base point per RFC 8032
+        let decoded = report.decoded.unwrap();
+        assert_eq!(
+            decoded.x.to_string(),
+            "15112221349535400772501151409588531511454012693041857206046113283949847762202"
+        );
+        assert_eq!(
+            decoded.y.to_string(),
+            "46316835694926478169428394003475163141307993866256225615783033603165251855960"
+        );
+
+        let mixed = ED25519_BASEPOINT_POINT + deserialize_point(&EIGHT_TORSION[3]).unwrap();
+        let report = inspect_point(mixed.compress().as_bytes()).unwrap();
+        assert_eq!(report.order().unwrap(), "8L");
+        assert_eq!(report.decoded.unwrap().torsion_index, 3);
+
+        // y = 2 is not on the curve
+        let mut two = [0u8; 32];
+        two[0] = 2;
+        let report = inspect_point(&two).unwrap();
+        assert!(report.decoded.is_none() && report.y_below_p);
+        assert!(inspect_point(&two[..31]).is_err());
+    }
+}
@@ -0,0 +1,249 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! The Rust verifiers linked into the crate, behind a common interface.
+
+use ed25519_dalek::Verifier;
+use rand::{rngs::StdRng, SeedableRng};
+use ring::signature;
+use std::convert::TryFrom;
+
+use crate::check_slice_size;
+use crate::signing::expand_seed;
+
+/// A verifier: its name in `results.md`, and whether it accepts
+/// `(message, pub_key, signature)`. Verifiers never panic on malformed input.
+pub struct Library {
+    pub name: &'static str,
+    pub verify: fn(&[u8], &[u8], &[u8]) -> bool,
+}
+
+pub const RUST_LIBRARIES: [Library; 10] = [
+    Library {
+        name: "BoringSSL",
+        verify: ring_verify,
+    },
+    Library {
+        name: "Dalek",
+        verify: dalek_verify,
+    },
+    Library {
+        name: "Dalek batch",
+        verify: dalek_verify_batch,
+    },
+    Library {
+        name: "Dalek strict",
+        verify: dalek_verify_strict,
+    },
+    // "Dalek" is ed25519-dalek 1.x, later major versions get their own rows
+    Library {
+        name: "Dalek 2",
+        verify: dalek2_verify,
+    },
+    Library {
+        name: "Dalek 2 strict",
+        verify: dalek2_verify_strict,
+    },
+    Library {
+        name: "Hacl*",
+        verify: hacl_verify,
+    },
+    // diem-crypto was published as libra-crypto
+    Library {
+        name: "libra-crypto",
+        verify: diem_verify,
+    },
+    Library {
+        name: "Zebra",
+        verify: zebra_verify,
+    },
+    Library {
+        name: "Zebra batch",
+        verify: zebra_verify_batch,
+    },
+];
+
+/// A valid signature, which batch verifiers check along with the signature
+/// under test, as batches are rarely made of a single signature
+fn honest_signature() -> (&'static [u8], [u8; 32], Vec<u8>) {
+    let message = b"honest signature";
+    let expanded = expand_seed(&[0u8; 32]);
+    (message, expanded.public_key(), expanded.sign(message))
+}
+
+fn ring_verify(message: &[u8], pub_key: &[u8], signature: &[u8]) -> bool {
+    <signature::EdDSAParameters as signature::VerificationAlgorithm>::verify(
+        &signature::ED25519,
+        untrusted::Input::from(pub_key),
+        untrusted::Input::from(message),
+        untrusted::Input::from(signature),
+    )
+    .is_ok()
+}
+
+fn dalek_verify(message: &[u8], pub_key: &[u8], signature: &[u8]) -> bool {
+    match (
+        ed25519_dalek::PublicKey::from_bytes(pub_key),
+        ed25519_dalek::Signature::try_from(signature),
+    ) {
+        (Ok(pk), Ok(sig)) => pk.verify(message, &sig).is_ok(),
+        _ => false,
+    }
+}
+
+fn dalek_verify_strict(message: &[u8], pub_key: &[u8], signature: &[u8]) -> bool {
+    match (
+        ed25519_dalek::PublicKey::from_bytes(pub_key),
+        ed25519_dalek::Signature::try_from(signature),
+    ) {
+        (Ok(pk), Ok(sig)) => pk.verify_strict(message, &sig).is_ok(),
+        _ => false,
+    }
+}
+
+fn dalek2_verify(message: &[u8], pub_key: &[u8], signature: &[u8]) -> bool {
+    use ed25519_dalek_2::Verifier;
+    match (
+        <[u8; 32]>::try_from(pub_key).map(|pk| ed25519_dalek_2::VerifyingKey::from_bytes(&pk)),
+        ed25519_dalek_2::Signature::from_slice(signature),
+    ) {
+        (Ok(Ok(pk)), Ok(sig)) => pk.verify(message, &sig).is_ok(),
+        _ => false,
+    }
+}
+
+fn dalek2_verify_strict(message: &[u8], pub_key: &[u8], signature: &[u8]) -> bool {
+    match (
+        <[u8; 32]>::try_from(pub_key).map(|pk| ed25519_dalek_2::VerifyingKey::from_bytes(&pk)),
+        ed25519_dalek_2::Signature::from_slice(signature),
+    ) {
+        (Ok(Ok(pk)), Ok(sig)) => pk.verify_strict(message, &sig).is_ok(),
+        _ => false,
+    }
+}
+
+/// `verify_batch` is deterministic with the `batch_deterministic` feature
+fn dalek_verify_batch(message: &[u8], pub_key: &[u8], signature: &[u8]) -> bool {
+    let (honest_message, honest_pub_key, honest_signature) = honest_signature();
+    match (
+        ed25519_dalek::PublicKey::from_bytes(pub_key),
+        ed25519_dalek::Signature::try_from(signature),
+        ed25519_dalek::PublicKey::from_bytes(&honest_pub_key),
+        ed25519_dalek::Signature::try_from(&honest_signature[..]),
+    ) {
+        (Ok(pk), Ok(sig), Ok(honest_pk), Ok(honest_sig)) => ed25519_dalek::verify_batch(
+            &[message, honest_message],
+            &[sig, honest_sig],
+            &[pk, honest_pk],
+        )
+        .is_ok(),
+        _ => false,
+    }
+}
+
+fn hacl_verify(message: &[u8], pub_key: &[u8], signature: &[u8]) -> bool {
+    match (
+        check_slice_size(pub_key, 32, "pub_key"),
+        check_slice_size(signature, 64, "signature"),
+    ) {
+        (Ok(pub_key), Ok(signature)) => {
+            let mut pk_bytes = [0u8; 32];
+            pk_bytes.copy_from_slice(pub_key);
+            let mut sig_bytes = [0u8; 64];
+            sig_bytes.copy_from_slice(signature);
+            hacl_star::ed25519::PublicKey(pk_bytes)
+                .verify(message, &hacl_star::ed25519::Signature(sig_bytes))
+        }
+        _ => false,
+    }
+}
+
+fn diem_verify(message: &[u8], pub_key: &[u8], signature: &[u8]) -> bool {
+    match (
+        diem_crypto::ed25519::Ed25519PublicKey::try_from(pub_key),
+        diem_crypto::ed25519::Ed25519Signature::try_from(signature),
+    ) {
+        (Ok(pk), Ok(sig)) => {
+            diem_crypto::traits::Signature::verify_arbitrary_msg(&sig, message, &pk).is_ok()
+        }
+        _ => false,
+    }
+}
+
+fn zebra_verify(message: &[u8], pub_key: &[u8], signature: &[u8]) -> bool {
+    match (
+        ed25519_zebra::VerificationKey::try_from(pub_key),
+        ed25519_zebra::Signature::try_from(signature),
+    ) {
+        (Ok(pk), Ok(sig)) => pk.verify(&sig, message).is_ok(),
+        _ => false,
+    }
+}
+
+fn zebra_verify_batch(message: &[u8], pub_key: &[u8], signature: &[u8]) -> bool {
+    let (honest_message, honest_pub_key, honest_signature) = honest_signature();
+    match (
+        ed25519_zebra::VerificationKeyBytes::try_from(pub_key),
+        ed25519_zebra::Signature::try_from(signature),
+        ed25519_zebra::Signature::try_from(&honest_signature[..]),
+    ) {
+        (Ok(pk), Ok(sig), Ok(honest_sig)) => {
+            let mut verifier = ed25519_zebra::batch::Verifier::new();
+            verifier.queue((pk, sig, message));
+            verifier.queue((
+                ed25519_zebra::VerificationKeyBytes::from(honest_pub_key),
+                honest_sig,
+                honest_message,
+            ));
+            // the coefficients are random, fix them for reproducible results
+            verifier.verify(StdRng::seed_from_u64(0)).is_ok()
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expected;
+    use crate::generate_test_vectors;
+    use crate::results::ResultRow;
+
+    #[test]
+    fn test_dalek_versions_and_batch_verifiers() {
+        let vec = generate_test_vectors();
+        for library in RUST_LIBRARIES.iter().filter(|l| l.name.ends_with(" batch")) {
+            let row = ResultRow::run(library, &vec).to_row_string();
+            println!("{}: {}", library.name, row);
+            expected::check_row(library.name, &row).unwrap();
+        }
+        // ed25519-dalek 2 behaves as 1.x on the test vectors
+        for name in ["Dalek 2", "Dalek 2 strict"].iter() {
+            let library = RUST_LIBRARIES.iter().find(|l| l.name == *name).unwrap();
+            let row = ResultRow::run(library, &vec).to_row_string();
+            expected::check_row(name, &row).unwrap();
+        }
+        assert_eq!(
+            expected::expected_row("Dalek 2"),
+            expected::expected_row("Dalek")
+        );
+        assert_eq!(
+            expected::expected_row("Dalek 2 strict"),
+            expected::expected_row("Dalek strict")
+        );
+
+        // Zebra's batch verification agrees with its single verification,
+        // Dalek's reduces the coefficients of torsion components mod L, and
+        // rejects signatures its single verification accepts (#1 to #3)
+        assert_eq!(
+            expected::expected_row("Zebra batch"),
+            expected::expected_row("Zebra")
+        );
+        assert_ne!(
+            expected::expected_row("Dalek batch"),
+            expected::expected_row("Dalek")
+        );
+    }
+}
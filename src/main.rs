@@ -12,6 +12,7 @@ use curve25519_dalek::{
 use rand::{rngs::StdRng, RngCore, SeedableRng};
 use sha2::{Digest, Sha512};
 
+use serde::de::{Deserialize, Deserializer};
 use serde::ser::{Serialize, SerializeStruct, Serializer};
 use std::fs::File;
 use std::io::prelude::*;
@@ -196,6 +197,18 @@ fn compute_hram_with_pk_array(
     Scalar::from_bytes_mod_order_wide(&k_output)
 }
 
+fn compute_hram_with_arrays(message: &[u8], pub_key_arr: &[u8], signature_r: &[u8]) -> Scalar {
+    let k_bytes = Sha512::default()
+        .chain(&signature_r)
+        .chain(&pub_key_arr)
+        .chain(&message);
+    // curve25519_dalek is stuck on an old digest version, so we can't do
+    // Scalar::from_hash
+    let mut k_output = [0u8; 64];
+    k_output.copy_from_slice(k_bytes.finalize().as_slice());
+    Scalar::from_bytes_mod_order_wide(&k_output)
+}
+
 fn verify_cofactored(
     message: &[u8],
     pub_key: &EdwardsPoint,
@@ -281,9 +294,10 @@ fn verify_final_cofactorless(
 // Cases //
 ///////////
 
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TestVector {
     #[allow(dead_code)]
-    message: [u8; 32],
+    message: Vec<u8>,
     #[allow(dead_code)]
     pub_key: [u8; 32],
     #[allow(dead_code)]
@@ -295,7 +309,7 @@ impl Serialize for TestVector {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("Color", 3)?;
+        let mut state = serializer.serialize_struct("TestVector", 3)?;
         state.serialize_field("message", &hex::encode(&self.message))?;
         state.serialize_field("pub_key", &hex::encode(&self.pub_key))?;
         state.serialize_field("signature", &hex::encode(&self.signature))?;
@@ -303,6 +317,36 @@ impl Serialize for TestVector {
     }
 }
 
+impl<'de> Deserialize<'de> for TestVector {
+    fn deserialize<D>(deserializer: D) -> Result<TestVector, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        struct HexTestVector {
+            message: String,
+            pub_key: String,
+            signature: String,
+        }
+
+        let tv = HexTestVector::deserialize(deserializer)?;
+        let decode = |field: &str, value: &str| {
+            hex::decode(value)
+                .map_err(|e| serde::de::Error::custom(format!("invalid {}: {}", field, e)))
+        };
+        let pub_key = decode("pub_key", &tv.pub_key)?;
+        let pub_key =
+            check_slice_size(&pub_key, 32, "pub_key").map_err(serde::de::Error::custom)?;
+        let mut pub_key_bytes = [0u8; 32];
+        pub_key_bytes.copy_from_slice(pub_key);
+        Ok(TestVector {
+            message: decode("message", &tv.message)?,
+            pub_key: pub_key_bytes,
+            signature: decode("signature", &tv.signature)?,
+        })
+    }
+}
+
 fn new_rng() -> impl RngCore {
     let mut pi_bytes = [0u8; 32];
     for i in 0..4 {
@@ -345,7 +389,7 @@ pub fn zero_small_small() -> Result<(TestVector, TestVector), anyhow::Error> {
         hex::encode(&serialize_signature(&r, &s))
     );
     let tv1 = TestVector {
-        message,
+        message: message.to_vec(),
         pub_key: pub_key.compress().to_bytes(),
         signature: serialize_signature(&r, &s),
     };
@@ -366,7 +410,7 @@ pub fn zero_small_small() -> Result<(TestVector, TestVector), anyhow::Error> {
         hex::encode(&serialize_signature(&r, &s))
     );
     let tv2 = TestVector {
-        message,
+        message: message.to_vec(),
         pub_key: pub_key.compress().to_bytes(),
         signature: serialize_signature(&r, &s),
     };
@@ -412,7 +456,7 @@ pub fn non_zero_mixed_small() -> Result<(TestVector, TestVector)> {
         hex::encode(&serialize_signature(&r, &s))
     );
     let tv1 = TestVector {
-        message,
+        message: message.to_vec(),
         pub_key: pub_key.compress().to_bytes(),
         signature: serialize_signature(&r, &s),
     };
@@ -431,7 +475,7 @@ pub fn non_zero_mixed_small() -> Result<(TestVector, TestVector)> {
         hex::encode(&serialize_signature(&r, &s))
     );
     let tv2 = TestVector {
-        message,
+        message: message.to_vec(),
         pub_key: pub_key.compress().to_bytes(),
         signature: serialize_signature(&r, &s),
     };
@@ -480,7 +524,7 @@ pub fn non_zero_small_mixed() -> Result<(TestVector, TestVector)> {
     );
 
     let tv1 = TestVector {
-        message,
+        message: message.to_vec(),
         pub_key: pub_key.compress().to_bytes(),
         signature: serialize_signature(&r, &s),
     };
@@ -500,7 +544,7 @@ pub fn non_zero_small_mixed() -> Result<(TestVector, TestVector)> {
         hex::encode(&serialize_signature(&r, &s))
     );
     let tv2 = TestVector {
-        message,
+        message: message.to_vec(),
         pub_key: pub_key.compress().to_bytes(),
         signature: serialize_signature(&r, &s),
     };
@@ -561,7 +605,7 @@ pub fn non_zero_mixed_mixed() -> Result<(TestVector, TestVector)> {
     );
 
     let tv1 = TestVector {
-        message,
+        message: message.to_vec(),
         pub_key: pub_key.compress().to_bytes(),
         signature: serialize_signature(&r, &s),
     };
@@ -590,7 +634,7 @@ pub fn non_zero_mixed_mixed() -> Result<(TestVector, TestVector)> {
         hex::encode(&serialize_signature(&r, &s))
     );
     let tv2 = TestVector {
-        message,
+        message: message.to_vec(),
         pub_key: pub_key.compress().to_bytes(),
         signature: serialize_signature(&r, &s),
     };
@@ -659,7 +703,7 @@ fn pre_reduced_scalar() -> TestVector {
         hex::encode(&serialize_signature(&r, &s))
     );
     TestVector {
-        message,
+        message: message.to_vec(),
         pub_key: pub_key.compress().to_bytes(),
         signature: serialize_signature(&r, &s),
     }
@@ -668,6 +712,53 @@ fn pre_reduced_scalar() -> TestVector {
 mod non_reducing_scalar52;
 use non_reducing_scalar52::Scalar52;
 
+mod signing;
+
+mod attack;
+
+mod policy;
+
+mod repudiation;
+
+mod malleability;
+
+mod ownership;
+
+mod grid;
+
+mod inspect;
+
+mod analyze;
+
+mod transcript;
+
+mod import;
+
+mod cases;
+
+mod published;
+
+mod validate;
+
+mod synth;
+
+// Only driven from the `fuzz_libraries` test
+#[cfg(test)]
+mod fuzz;
+
+// The library harnesses, built for the tests or with the `libraries` feature
+#[cfg(any(test, feature = "libraries"))]
+mod expected;
+
+#[cfg(any(test, feature = "libraries"))]
+mod libraries;
+
+#[cfg(any(test, feature = "libraries"))]
+mod results;
+
+#[cfg(any(test, feature = "libraries"))]
+mod external;
+
 ////////
 // 9  //
 ////////
@@ -722,7 +813,7 @@ fn large_s() -> Result<TestVector> {
         hex::encode(&serialize_signature(&r, &s_prime))
     );
     let tv = TestVector {
-        message,
+        message: message.to_vec(),
         pub_key: pub_key.compress().to_bytes(),
         signature: serialize_signature(&r, &s_prime),
     };
@@ -789,7 +880,7 @@ fn really_large_s() -> Result<TestVector> {
         hex::encode(&serialize_signature(&r, &s_prime))
     );
     let tv = TestVector {
-        message,
+        message: message.to_vec(),
         pub_key: pub_key.compress().to_bytes(),
         signature: serialize_signature(&r, &s_prime),
     };
@@ -848,7 +939,7 @@ pub fn non_zero_small_non_canonical_mixed() -> Result<Vec<TestVector>> {
         hex::encode(&signature)
     );
     let tv1 = TestVector {
-        message,
+        message: message.to_vec(),
         pub_key: pub_key.compress().to_bytes(),
         signature,
     };
@@ -866,7 +957,7 @@ pub fn non_zero_small_non_canonical_mixed() -> Result<Vec<TestVector>> {
         hex::encode(&signature)
     );
     let tv2 = TestVector {
-        message,
+        message: message.to_vec(),
         pub_key: pub_key.compress().to_bytes(),
         signature,
     };
@@ -927,7 +1018,7 @@ pub fn non_zero_mixed_small_non_canonical() -> Result<Vec<TestVector>> {
         hex::encode(&serialize_signature(&r, &s))
     );
     let tv1 = TestVector {
-        message,
+        message: message.to_vec(),
         pub_key: pub_key_arr,
         signature: serialize_signature(&r, &s),
     };
@@ -952,7 +1043,7 @@ pub fn non_zero_mixed_small_non_canonical() -> Result<Vec<TestVector>> {
         hex::encode(&serialize_signature(&r, &s))
     );
     let tv2 = TestVector {
-        message,
+        message: message.to_vec(),
         pub_key: pub_key_arr,
         signature: serialize_signature(&r, &s),
     };
@@ -1047,25 +1138,256 @@ fn generate_test_vectors() -> Vec<TestVector> {
     vec
 }
 
+const USAGE: &str = "\
+usage: ed25519-speccheck [command]
+
+commands:
+    generate                          write the test vector files (default)
+    results                           run the Rust libraries over the test vectors,
+                                      and update results.md, .json and .csv
+    check [library ...]               re-run the harness of each library with one,
+                                      and compare to expected_results.json
+    external <name> <command> [...]   run an external verifier over the test
+                                      vectors (see README), and add its row to
+                                      results.md, .json and .csv
+    check-sign <seed> <msg> <sig>     compare a signature of msg under seed with
+                                      the RFC 8032 reference (hex arguments)
+    verify-published [dir]            regenerate the test vectors, and compare them
+                                      with the files generate wrote to dir
+    inspect point <hex>               decode and classify a point encoding
+    analyze <msg> <pk> <sig>          classify a signature's components, and give
+                                      the outcome under each reference policy
+                                      (hex arguments)
+    trace <msg> <pk> <sig>            print the intermediate values of each reference
+                                      policy's verification as JSON (hex arguments)
+    import rfc8032|wycheproof <file>  load external test vectors, and print them
+                                      classified, as JSON
+    convert <from> <in> <to> <out>    convert test vectors between formats: json,
+                                      txt (cases.json and cases.txt), and as inputs
+                                      only, rfc8032 and wycheproof
+    synthesize <A> <R> <S> <outcomes> grind a vector with A and R of the given
+                                      orders (1 to 8L, :nc for non-canonical), S
+                                      zero, reduced or unreduced, and outcomes
+                                      V, X or ? under each reference policy
+    attack <msg> <pk> <sig> [...]     try to recover the secret scalar from one or
+                                      more signatures (hex arguments)
+
+results, check and external run the verifiers, and need the libraries feature:
+    cargo run --features libraries -- results";
+
 fn main() -> Result<()> {
     env_logger::init();
-    let vec = generate_test_vectors();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        None | Some("generate") => write_test_vectors(),
+        #[cfg(any(test, feature = "libraries"))]
+        Some("results") => {
+            for row in results::write_results(std::path::Path::new("."))? {
+                println!("{}", row.to_markdown());
+            }
+            Ok(())
+        }
+        #[cfg(any(test, feature = "libraries"))]
+        Some("check") => expected::check(&args[1..]),
+        #[cfg(any(test, feature = "libraries"))]
+        Some("external") if args.len() >= 3 => {
+            let row = external::run_external(&args[1], &args[2], &args[3..])?;
+            println!("{}", row.to_markdown());
+            results::update_results(std::path::Path::new("."), vec![row])?;
+            Ok(())
+        }
+        #[cfg(not(any(test, feature = "libraries")))]
+        Some(command @ "results") | Some(command @ "check") | Some(command @ "external") => {
+            Err(anyhow!(
+                "{} runs the verifiers: cargo run --features libraries -- {}",
+                command,
+                command
+            ))
+        }
+        Some("check-sign") if args.len() == 4 => {
+            let check = signing::check_signature(
+                &hex::decode(&args[1])?,
+                &hex::decode(&args[2])?,
+                &hex::decode(&args[3])?,
+            )?;
+            println!("{}", check);
+            Ok(())
+        }
+        Some("analyze") if args.len() == 4 => {
+            let analysis = analyze::analyze(
+                &hex::decode(&args[1])?,
+                &hex::decode(&args[2])?,
+                &hex::decode(&args[3])?,
+            )?;
+            println!("{}", analysis);
+            Ok(())
+        }
+        Some("trace") if args.len() == 4 => {
+            let (message, pub_key, signature) = (
+                hex::decode(&args[1])?,
+                hex::decode(&args[2])?,
+                hex::decode(&args[3])?,
+            );
+            let transcripts = policy::REFERENCE_POLICIES
+                .iter()
+                .map(|p| transcript::trace(p, &message, &pub_key, &signature))
+                .collect::<Result<Vec<_>>>()?;
+            println!("{}", serde_json::to_string_pretty(&transcripts)?);
+            Ok(())
+        }
+        Some("import") if args.len() == 3 => {
+            let contents = std::fs::read_to_string(&args[2])?;
+            let vec = match args[1].as_str() {
+                "rfc8032" => import::load_rfc8032(&contents)?,
+                "wycheproof" => import::load_wycheproof(&contents)?,
+                other => return Err(anyhow!("unknown vector format {}\n{}", other, USAGE)),
+            };
+            println!("{}", serde_json::to_string_pretty(&vec)?);
+            Ok(())
+        }
+        Some("convert") if args.len() == 5 => {
+            let input = std::fs::read_to_string(&args[2])?;
+            let output = cases::convert(&args[1], &input, &args[3])?;
+            let mut file = File::create(&args[4])?;
+            file.write_all(output.as_bytes())?;
+            Ok(())
+        }
+        Some("verify-published") if args.len() <= 2 => {
+            let dir = args.get(1).map(String::as_str).unwrap_or(".");
+            published::verify_dir(std::path::Path::new(dir))?;
+            println!("the published vectors are reproducible");
+            Ok(())
+        }
+        Some("inspect") if args.len() == 3 && args[1] == "point" => {
+            println!("{}", inspect::inspect_point(&hex::decode(&args[2])?)?);
+            Ok(())
+        }
+        Some("synthesize") if args.len() == 5 => {
+            let spec = synth::Spec::parse(&args[1], &args[2], &args[3], &args[4])?;
+            let synthesized = synth::synthesize(&spec, synth::MAX_ATTEMPTS)?;
+            println!("{}", serde_json::to_string_pretty(&synthesized)?);
+            Ok(())
+        }
+        Some("attack") if args.len() >= 4 && args.len() % 3 == 1 => {
+            let decoded = args[1..]
+                .iter()
+                .map(hex::decode)
+                .collect::<Result<Vec<_>, _>>()?;
+            let sms: Vec<attack::SignedMessage> = decoded
+                .chunks(3)
+                .map(|triple| attack::SignedMessage {
+                    message: &triple[0],
+                    pub_key: &triple[1],
+                    signature: &triple[2],
+                })
+                .collect();
+            let (recovered, report) = attack::attack(&sms);
+            for line in report.iter() {
+                println!("{}", line);
+            }
+            if recovered.is_none() {
+                println!("could not recover the secret scalar");
+            }
+            Ok(())
+        }
+        _ => Err(anyhow!("{}", USAGE)),
+    }
+}
+
+/// The files written by `generate` for the test vectors `vec`, with their
+/// contents. Each set of vectors is validated before it is serialized, in all
+/// builds unlike the generators' debug_assert!s, so that an error here comes
+/// before any file is written.
+fn test_vector_files(vec: &[TestVector]) -> Result<Vec<(&'static str, String)>> {
+    validate::validate(vec)?;
+    let mut files = Vec::new();
 
     // Write test vectors to json
-    let cases_json = serde_json::to_string(&vec)?;
-    let mut file = File::create("cases.json")?;
-    file.write_all(cases_json.as_bytes())?;
+    files.push(("cases.json", serde_json::to_string(&vec)?));
 
     // Write test vectors to txt (to ease testing C implementations)
-    let mut file = File::create("cases.txt")?;
-    file.write_all(vec.len().to_string().as_bytes())?;
-    for tv in vec.iter() {
-        file.write_all(b"\nmsg=")?;
-        file.write_all(hex::encode(&tv.message).as_bytes())?;
-        file.write_all(b"\npbk=")?;
-        file.write_all(hex::encode(&tv.pub_key).as_bytes())?;
-        file.write_all(b"\nsig=")?;
-        file.write_all(hex::encode(&tv.signature).as_bytes())?;
+    files.push(("cases.txt", cases::to_txt(vec)));
+
+    // Write key generation vectors to json
+    let keygen_vec = signing::keygen_clamping_vectors();
+    validate::validate_keygen(&keygen_vec)?;
+    files.push(("keygen_cases.json", serde_json::to_string(&keygen_vec)?));
+
+    // Write deterministic signing vectors to json
+    let signing_vec = signing::signing_vectors();
+    validate::validate_signing(&signing_vec)?;
+    files.push(("signing_cases.json", serde_json::to_string(&signing_vec)?));
+
+    // Write non-repudiation vectors to json and txt
+    let repudiation_vec = repudiation::repudiation_vectors();
+    validate::validate_repudiation(&repudiation_vec)?;
+    files.push((
+        "repudiation_cases.json",
+        serde_json::to_string(&repudiation_vec)?,
+    ));
+
+    let mut repudiation_txt = repudiation_vec.len().to_string();
+    for tv in repudiation_vec.iter() {
+        repudiation_txt.push_str("\npbk=");
+        repudiation_txt.push_str(&hex::encode(&tv.pub_key));
+        repudiation_txt.push_str("\nsig=");
+        repudiation_txt.push_str(&hex::encode(&tv.signature));
+        repudiation_txt.push_str("\nmsg1=");
+        repudiation_txt.push_str(&hex::encode(&tv.message1));
+        repudiation_txt.push_str("\nmsg2=");
+        repudiation_txt.push_str(&hex::encode(&tv.message2));
+    }
+    files.push(("repudiation_cases.txt", repudiation_txt));
+
+    // Write public key substitution vectors to json
+    let substitution_vec = signing::key_substitution_vectors();
+    validate::validate_key_substitution(&substitution_vec)?;
+    files.push((
+        "key_substitution_cases.json",
+        serde_json::to_string(&substitution_vec)?,
+    ));
+
+    // Write malleability vectors to json
+    let malleability_vec = malleability::malleability_vectors();
+    validate::validate_malleability(&malleability_vec)?;
+    files.push((
+        "malleability_cases.json",
+        serde_json::to_string(&malleability_vec)?,
+    ));
+
+    // Write exclusive ownership vectors to json and txt
+    let ownership_vec = ownership::ownership_vectors();
+    validate::validate_ownership(&ownership_vec)?;
+    files.push((
+        "ownership_cases.json",
+        serde_json::to_string(&ownership_vec)?,
+    ));
+
+    let mut ownership_txt = ownership_vec.len().to_string();
+    for tv in ownership_vec.iter() {
+        ownership_txt.push_str("\nmsg=");
+        ownership_txt.push_str(&hex::encode(&tv.message));
+        ownership_txt.push_str("\npbk1=");
+        ownership_txt.push_str(&hex::encode(&tv.pub_key1));
+        ownership_txt.push_str("\npbk2=");
+        ownership_txt.push_str(&hex::encode(&tv.pub_key2));
+        ownership_txt.push_str("\nsig=");
+        ownership_txt.push_str(&hex::encode(&tv.signature));
+    }
+    files.push(("ownership_cases.txt", ownership_txt));
+
+    // Write the A order × R order × S class grid to json
+    let grid_vec = grid::grid_vectors();
+    validate::validate_grid(&grid_vec)?;
+    files.push(("grid_cases.json", serde_json::to_string(&grid_vec)?));
+    Ok(files)
+}
+
+fn write_test_vectors() -> Result<()> {
+    let vec = generate_test_vectors();
+    for (name, contents) in test_vector_files(&vec)? {
+        let mut file = File::create(name)?;
+        file.write_all(contents.as_bytes())?;
     }
     Ok(())
 }
@@ -1103,6 +1425,16 @@ mod tests {
         (pk, sig)
     }
 
+    fn print_result(row: &mut String, accepted: bool) {
+        if accepted {
+            print!(" V |");
+            row.push('V');
+        } else {
+            print!(" X |");
+            row.push('X');
+        }
+    }
+
     fn ring_verify(t: &TestVector) -> Result<()> {
         let pk = untrusted::Input::from(&t.pub_key[..]);
         let sig = untrusted::Input::from(&t.signature[..]);
@@ -1121,27 +1453,30 @@ mod tests {
         let vec = generate_test_vectors();
 
         print!("\n|diem-crypto   |");
+        let mut row = String::new();
         for tv in vec.iter() {
             let pk = match diem_crypto::ed25519::Ed25519PublicKey::try_from(&tv.pub_key[..]) {
                 Ok(pk) => pk,
                 Err(_e) => {
-                    print!(" X |");
+                    print_result(&mut row, false);
                     continue;
                 }
             };
             let sig = match diem_crypto::ed25519::Ed25519Signature::try_from(&tv.signature[..]) {
                 Ok(sig) => sig,
                 Err(_e) => {
-                    print!(" X |");
+                    print_result(&mut row, false);
                     continue;
                 }
             };
             match diem_crypto::traits::Signature::verify_arbitrary_msg(&sig, &tv.message[..], &pk) {
-                Ok(_v) => print!(" V |"),
-                Err(_e) => print!(" X |"),
+                Ok(_v) => print_result(&mut row, true),
+                Err(_e) => print_result(&mut row, false),
             }
         }
         println!();
+        // diem-crypto was published as libra-crypto
+        expected::check_row("libra-crypto", &row).unwrap();
     }
 
     #[test]
@@ -1149,15 +1484,17 @@ mod tests {
         let vec = generate_test_vectors();
 
         print!("\n|Hacl*          |");
+        let mut row = String::new();
         for tv in vec.iter() {
             let (pk, sig) = unpack_test_vector_hacl(&tv);
             if pk.verify(&tv.message[..], &sig) {
-                print!(" V |");
+                print_result(&mut row, true);
             } else {
-                print!(" X |");
+                print_result(&mut row, false);
             }
         }
         println!();
+        expected::check_row("Hacl*", &row).unwrap();
     }
 
     #[test]
@@ -1165,22 +1502,24 @@ mod tests {
         let vec = generate_test_vectors();
 
         print!("\n|Dalek          |");
+        let mut row = String::new();
         for tv in vec.iter() {
             match Signature::try_from(&tv.signature[..]) {
                 Ok(_v) => {}
                 Err(_e) => {
-                    print!(" X |");
+                    print_result(&mut row, false);
                     continue;
                 }
             }
 
             let (pk, sig) = unpack_test_vector_dalek(&tv);
             match pk.verify(&tv.message[..], &sig) {
-                Ok(_v) => print!(" V |"),
-                Err(_e) => print!(" X |"),
+                Ok(_v) => print_result(&mut row, true),
+                Err(_e) => print_result(&mut row, false),
             }
         }
         println!();
+        expected::check_row("Dalek", &row).unwrap();
     }
 
     #[test]
@@ -1188,22 +1527,24 @@ mod tests {
         let vec = generate_test_vectors();
 
         print!("\n|Dalek strict   |");
+        let mut row = String::new();
         for tv in vec.iter() {
             match Signature::try_from(&tv.signature[..]) {
                 Ok(_v) => {}
                 Err(_e) => {
-                    print!(" X |");
+                    print_result(&mut row, false);
                     continue;
                 }
             }
 
             let (pk, sig) = unpack_test_vector_dalek(&tv);
             match pk.verify_strict(&tv.message[..], &sig) {
-                Ok(_v) => print!(" V |"),
-                Err(_e) => print!(" X |"),
+                Ok(_v) => print_result(&mut row, true),
+                Err(_e) => print_result(&mut row, false),
             }
         }
         println!();
+        expected::check_row("Dalek strict", &row).unwrap();
     }
 
     #[test]
@@ -1211,13 +1552,15 @@ mod tests {
         let vec = generate_test_vectors();
 
         print!("\n|BoringSSL      |");
+        let mut row = String::new();
         for tv in vec.iter() {
             match ring_verify(&tv) {
-                Ok(_v) => print!(" V |"),
-                Err(_e) => print!(" X |"),
+                Ok(_v) => print_result(&mut row, true),
+                Err(_e) => print_result(&mut row, false),
             }
         }
         println!();
+        expected::check_row("BoringSSL", &row).unwrap();
     }
 
     #[test]
@@ -1225,70 +1568,248 @@ mod tests {
         let vec = generate_test_vectors();
 
         print!("\n|Zebra          |");
+        let mut row = String::new();
         for tv in vec.iter() {
             match Signature::try_from(&tv.signature[..]) {
                 Ok(_v) => {}
                 Err(_e) => {
-                    print!(" X |");
+                    print_result(&mut row, false);
                     continue;
                 }
             }
 
             let (pk, sig) = unpack_test_vector_zebra(&tv);
             match pk.verify(&sig, &tv.message[..]) {
-                Ok(_v) => print!(" V |"),
-                Err(_e) => print!(" X |"),
+                Ok(_v) => print_result(&mut row, true),
+                Err(_e) => print_result(&mut row, false),
             }
         }
         println!();
+        expected::check_row("Zebra", &row).unwrap();
+    }
+
+    #[test]
+    fn test_keygen_dalek() {
+        for tv in signing::keygen_clamping_vectors() {
+            let secret = ed25519_dalek::SecretKey::from_bytes(&tv.seed[..]).unwrap();
+            let public = PublicKey::from(&secret);
+            let expanded = ed25519_dalek::ExpandedSecretKey::from(&secret);
+            assert_eq!(public.to_bytes(), tv.pub_key, "{}", tv.comment);
+            assert_eq!(
+                &expanded.sign(&tv.message[..], &public).to_bytes()[..],
+                &tv.signature[..],
+                "{}",
+                tv.comment
+            );
+
+            // An externally supplied expanded key is taken as is, but for bit
+            // 255, which `Scalar::from_bits` clears: dalek signs with the
+            // unclamped scalar, or a partially clamped one if bit 255 is set,
+            // while it still clamps to derive the public key
+            let supplied = ed25519_dalek::ExpandedSecretKey::from_bytes(&tv.expanded_key).unwrap();
+            assert_eq!(
+                PublicKey::from(&supplied).to_bytes(),
+                tv.pub_key,
+                "{}",
+                tv.comment
+            );
+            let mut raw_scalar = [0u8; 32];
+            raw_scalar.copy_from_slice(&tv.expanded_key[..32]);
+            if raw_scalar[31] & 128 == 0 {
+                let unclamped = PublicKey::from_bytes(&tv.unclamped_pub_key).unwrap();
+                assert_eq!(
+                    &supplied.sign(&tv.message[..], &unclamped).to_bytes()[..],
+                    &tv.unclamped_signature[..],
+                    "{}",
+                    tv.comment
+                );
+            } else {
+                raw_scalar[31] &= 127;
+                let partially_clamped = Scalar::from_bytes_mod_order(raw_scalar);
+                let partial_pub_key = PublicKey::from_bytes(
+                    (partially_clamped * ED25519_BASEPOINT_POINT)
+                        .compress()
+                        .as_bytes(),
+                )
+                .unwrap();
+                let signature = supplied.sign(&tv.message[..], &partial_pub_key).to_bytes();
+                assert_eq!(
+                    &signature[..],
+                    &signing::expand_seed(&tv.seed)
+                        .sign_with_scalar(&partially_clamped, &tv.message)[..],
+                    "{}",
+                    tv.comment
+                );
+                assert_ne!(
+                    &signature[..],
+                    &tv.unclamped_signature[..],
+                    "{}",
+                    tv.comment
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_signing_dalek() {
+        for tv in signing::signing_vectors() {
+            let secret = ed25519_dalek::SecretKey::from_bytes(&tv.seed[..]).unwrap();
+            let public = PublicKey::from(&secret);
+            let expanded = ed25519_dalek::ExpandedSecretKey::from(&secret);
+            let signature = expanded.sign(&tv.message[..], &public).to_bytes();
+            assert_eq!(
+                signing::check_signature(&tv.seed, &tv.message, &signature).unwrap(),
+                signing::SigningCheck::Identical,
+                "{}",
+                tv.comment
+            );
+        }
+    }
+
+    #[test]
+    fn test_double_public_key_dalek() {
+        // ed25519-dalek 1.x's `ExpandedSecretKey::sign` takes the public key
+        // from the caller, which makes it a double public key oracle
+        for tv in signing::key_substitution_vectors() {
+            let secret = ed25519_dalek::SecretKey::from_bytes(&tv.seed[..]).unwrap();
+            let expanded = ed25519_dalek::ExpandedSecretKey::from(&secret);
+            let supplied = PublicKey::from_bytes(&tv.supplied_pub_key[..]).unwrap();
+            let oracle_signature = expanded.sign(&tv.message[..], &supplied).to_bytes();
+            assert_eq!(&oracle_signature[..], &tv.oracle_signature[..], "{}", tv.comment);
+
+            let a = signing::recover_from_double_public_key(
+                &tv.message,
+                &tv.pub_key,
+                &tv.signature,
+                &tv.supplied_pub_key,
+                &oracle_signature,
+            )
+            .unwrap();
+            assert_eq!(a, signing::expand_seed(&tv.seed).scalar().reduce());
+        }
     }
 
     #[test]
     fn test_repudiation_dalek() {
-        // Pick a random Scalar
-        let mut rng = new_rng();
-        let mut scalar_bytes = [0u8; 32];
-        rng.fill_bytes(&mut scalar_bytes);
-        let s = Scalar::from_bytes_mod_order(scalar_bytes);
-        debug_assert!(s.is_canonical());
-        debug_assert!(s != Scalar::zero());
-
-        let r0 = s * ED25519_BASEPOINT_POINT;
-        // Pick a torsion point of order 2
-        let pub_key = deserialize_point(&EIGHT_TORSION[4]).unwrap();
-        let r = r0 + pub_key.neg();
-
-        let message1 = b"Send 100 USD to Alice";
-        let message2 = b"Send 100000 USD to Alice";
-
-        debug_assert!(
-            (pub_key.neg() + compute_hram(message1, &pub_key, &r) * pub_key).is_identity()
-        );
-        debug_assert!(
-            (pub_key.neg() + compute_hram(message2, &pub_key, &r) * pub_key).is_identity()
-        );
-
-        debug_assert!(verify_cofactored(message1, &pub_key, &(r, s)).is_ok());
-        debug_assert!(verify_cofactorless(message1, &pub_key, &(r, s)).is_ok());
-        debug_assert!(verify_cofactored(message2, &pub_key, &(r, s)).is_ok());
-        debug_assert!(verify_cofactorless(message2, &pub_key, &(r, s)).is_ok());
-
-        println!(
-            "Small pk breaks non-repudiation:\n\
-             \"pub_key\": \"{}\",\n\
-             \"signature\": \"{}\",\n\
-             \"message1\": \"{}\",\n\
-             \"message2\": \"{}\"",
-            hex::encode(&pub_key.compress().as_bytes()),
-            hex::encode(&serialize_signature(&r, &s)),
-            hex::encode(&message1),
-            hex::encode(&message2),
-        );
-
-        let signature = serialize_signature(&r, &s);
-        let pk = PublicKey::from_bytes(&pub_key.compress().as_bytes()[..]).unwrap();
-        let sig = Signature::try_from(&signature[..]).unwrap();
-        debug_assert!(pk.verify(message1, &sig).is_ok());
-        debug_assert!(pk.verify(message2, &sig).is_ok());
+        for tv in repudiation::repudiation_vectors() {
+            println!(
+                "Small pk of order {} breaks non-repudiation under {} verification:\n\
+                 \"pub_key\": \"{}\",\n\
+                 \"signature\": \"{}\",\n\
+                 \"message1\": \"{}\",\n\
+                 \"message2\": \"{}\"",
+                tv.torsion_order,
+                tv.policy,
+                hex::encode(&tv.pub_key),
+                hex::encode(&tv.signature),
+                hex::encode(&tv.message1),
+                hex::encode(&tv.message2),
+            );
+
+            // Dalek's `verify` is cofactorless, and accepts small order keys
+            let dalek_verify = |msg: &[u8], pk: &[u8; 32], sig: &[u8]| {
+                let pk = PublicKey::from_bytes(&pk[..]).unwrap();
+                let sig = Signature::try_from(sig).unwrap();
+                pk.verify(msg, &sig).is_ok()
+            };
+            assert_eq!(
+                tv.check_binding(dalek_verify).is_err(),
+                tv.accepted_by.contains(&"cofactorless")
+            );
+
+            // `verify_strict` rejects small order keys
+            let dalek_verify_strict = |msg: &[u8], pk: &[u8; 32], sig: &[u8]| {
+                let pk = PublicKey::from_bytes(&pk[..]).unwrap();
+                let sig = Signature::try_from(sig).unwrap();
+                pk.verify_strict(msg, &sig).is_ok()
+            };
+            assert!(tv.check_binding(dalek_verify_strict).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_malleability_dalek() {
+        for tv in malleability::malleability_vectors() {
+            let pk = PublicKey::from_bytes(&tv.pub_key[..]).unwrap();
+            let (verify, verify_strict) = match Signature::try_from(&tv.signature[..]) {
+                Ok(sig) => (
+                    pk.verify(&tv.message, &sig).is_ok(),
+                    pk.verify_strict(&tv.message, &sig).is_ok(),
+                ),
+                Err(_) => (false, false),
+            };
+            println!(
+                "{} ({:?}): verify {}, verify_strict {}",
+                tv.comment, tv.producer, verify, verify_strict
+            );
+            // Every signature a third party can derive from another breaks SUF-CMA
+            if tv.producer == malleability::Producer::ThirdParty {
+                assert!(!verify && !verify_strict, "{}", tv.comment);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ownership_dalek() {
+        for tv in ownership::ownership_vectors() {
+            println!(
+                "{} share a signature under {} verification:\n\
+                 \"message\": \"{}\",\n\
+                 \"pub_key1\": \"{}\",\n\
+                 \"pub_key2\": \"{}\",\n\
+                 \"signature\": \"{}\"",
+                tv.comment,
+                tv.policy,
+                hex::encode(&tv.message),
+                hex::encode(&tv.pub_key1),
+                hex::encode(&tv.pub_key2),
+                hex::encode(&tv.signature),
+            );
+
+            // Dalek's `verify` is cofactorless, hashes A and R as received, and
+            // accepts small order keys
+            let dalek_verify = |msg: &[u8], pk: &[u8; 32], sig: &[u8]| {
+                let pk = PublicKey::from_bytes(&pk[..]).unwrap();
+                let sig = Signature::try_from(sig).unwrap();
+                pk.verify(msg, &sig).is_ok()
+            };
+            assert_eq!(
+                tv.check_ownership(dalek_verify).is_err(),
+                tv.accepted_by.contains(&"cofactorless, raw hash")
+            );
+
+            // `verify_strict` rejects small order keys
+            let dalek_verify_strict = |msg: &[u8], pk: &[u8; 32], sig: &[u8]| {
+                let pk = PublicKey::from_bytes(&pk[..]).unwrap();
+                let sig = Signature::try_from(sig).unwrap();
+                pk.verify_strict(msg, &sig).is_ok()
+            };
+            assert!(tv.check_ownership(dalek_verify_strict).is_ok());
+        }
+    }
+
+    /// Differential fuzzing of the verified libraries against the reference
+    /// policies. Run with `cargo test fuzz_libraries -- --ignored --nocapture`,
+    /// optionally setting `FUZZ_ITERATIONS` and `FUZZ_SEED`.
+    #[test]
+    #[ignore]
+    fn fuzz_libraries() {
+        let iterations = std::env::var("FUZZ_ITERATIONS")
+            .map(|n| n.parse().expect("FUZZ_ITERATIONS is not a number"))
+            .unwrap_or(10_000);
+        let mut rng: Box<dyn RngCore> = match std::env::var("FUZZ_SEED") {
+            Ok(seed) => Box::new(StdRng::seed_from_u64(
+                seed.parse().expect("FUZZ_SEED is not a number"),
+            )),
+            Err(_) => Box::new(new_rng()),
+        };
+
+        let verifiers: Vec<fuzz::Verifier> = libraries::RUST_LIBRARIES
+            .iter()
+            .map(|library| -> fuzz::Verifier { (library.name, &library.verify) })
+            .collect();
+        for report in fuzz::fuzz(&mut rng, iterations, &verifiers) {
+            println!("{}", report.describe());
+        }
     }
 }
@@ -0,0 +1,317 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Strong unforgeability (malleability) vectors.
+//!
+//! Starting from one honest signature, we list every other signature of the
+//! same message we know how to produce, and which reference policies accept
+//! it. Variants produced by a third party (without the secret key) break
+//! SUF-CMA for the policies accepting them. Variants only the signer can
+//! produce do not, but still show that signatures are not unique under those
+//! policies, which matters for protocols using signatures as identifiers.
+
+use anyhow::Result;
+use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+use rand::RngCore;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::convert::TryInto;
+
+use crate::non_reducing_scalar52::Scalar52;
+use crate::policy::REFERENCE_POLICIES;
+use crate::signing::expand_seed;
+use crate::{
+    check_slice_size, compute_hram, deserialize_point, new_rng, serialize_signature, EIGHT_TORSION,
+};
+
+/// Who can produce a variant of a signature
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Producer {
+    /// Anyone holding the original signature
+    ThirdParty,
+    /// Only the holder of the secret key
+    Signer,
+}
+
+/// How a signature was derived from the honest one
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    /// The honest signature itself
+    Honest,
+    /// S + nL, same R
+    SPlusMultipleOfL,
+    /// R + T for a non-trivial torsion point T, same S
+    RPlusTorsion,
+    /// An alternative encoding of R, same S
+    ReencodedR,
+    /// R + T, with S recomputed for the new hash
+    RPlusTorsionResigned,
+}
+
+pub struct MalleabilityVector {
+    pub comment: String,
+    pub variant: Variant,
+    pub producer: Producer,
+    pub message: [u8; 32],
+    pub pub_key: [u8; 32],
+    pub signature: Vec<u8>,
+    pub accepted_by: Vec<&'static str>,
+}
+
+impl Serialize for MalleabilityVector {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("MalleabilityVector", 7)?;
+        state.serialize_field("comment", &self.comment)?;
+        state.serialize_field(
+            "variant",
+            match self.variant {
+                Variant::Honest => "honest",
+                Variant::SPlusMultipleOfL => "S + nL",
+                Variant::RPlusTorsion => "R + T",
+                Variant::ReencodedR => "R re-encoded",
+                Variant::RPlusTorsionResigned => "R + T, S recomputed",
+            },
+        )?;
+        state.serialize_field(
+            "producer",
+            match self.producer {
+                Producer::ThirdParty => "third party",
+                Producer::Signer => "signer",
+            },
+        )?;
+        state.serialize_field("message", &hex::encode(&self.message))?;
+        state.serialize_field("pub_key", &hex::encode(&self.pub_key))?;
+        state.serialize_field("signature", &hex::encode(&self.signature))?;
+        state.serialize_field("accepted_by", &self.accepted_by)?;
+        state.end()
+    }
+}
+
+/// The encodings of the point encoded as `bytes`, other than `bytes` itself:
+/// `y + p` when `y < 19`, `y - p` when `y ≥ p`, and a flipped sign bit when
+/// `x = 0`, together with a description of each.
+pub fn alternative_encodings(bytes: &[u8; 32]) -> Vec<([u8; 32], &'static str)> {
+    let point = match deserialize_point(bytes) {
+        Ok(point) => point,
+        Err(_) => return Vec::new(),
+    };
+
+    // p = 2^255 - 19, as 255 bits
+    let mut p = [0xffu8; 32];
+    p[0] = 0xed;
+    p[31] = 0x7f;
+    let mut y = *bytes;
+    y[31] &= 0x7f;
+    let y = Scalar52::from_bytes(&y);
+    let p = Scalar52::from_bytes(&p);
+    let sign = bytes[31] & 0x80;
+
+    let mut candidates = Vec::new();
+    let mut flipped = *bytes;
+    flipped[31] ^= 0x80;
+    candidates.push((flipped, "flipped sign bit"));
+    let other_y = if y < p {
+        Scalar52::checked_add(&y, &p)
+            .filter(|y_plus_p| y_plus_p.is_below_pow2(255))
+            .map(|y_plus_p| (y_plus_p, "y + p"))
+    } else {
+        Scalar52::checked_sub(&y, &p).map(|y_minus_p| (y_minus_p, "y - p"))
+    };
+    if let Some((other_y, comment)) = other_y {
+        let mut encoding = other_y.to_bytes();
+        encoding[31] |= sign;
+        candidates.push((encoding, comment));
+        let mut flipped = encoding;
+        flipped[31] ^= 0x80;
+        candidates.push((
+            flipped,
+            if y < p {
+                "y + p, flipped sign bit"
+            } else {
+                "y - p, flipped sign bit"
+            },
+        ));
+    }
+
+    candidates
+        .into_iter()
+        .filter(|(encoding, _)| {
+            deserialize_point(encoding)
+                .map(|other| other == point)
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+fn accepted_by(message: &[u8], pub_key: &[u8], signature: &[u8]) -> Vec<&'static str> {
+    REFERENCE_POLICIES
+        .iter()
+        .filter(|p| p.verify(message, pub_key, signature).is_ok())
+        .map(|p| p.name)
+        .collect()
+}
+
+/// Every variant of `signature` that a third party can produce: S + nL for
+/// all n keeping S below 2^256, R + T for each non-trivial torsion point T,
+/// and every alternative encoding of R.
+pub fn third_party_variants(signature: &[u8]) -> Result<Vec<(Vec<u8>, Variant, String)>> {
+    let signature = check_slice_size(signature, 64, "signature")?;
+    let r_bytes: [u8; 32] = signature[..32].try_into()?;
+    let s_bytes: [u8; 32] = signature[32..].try_into()?;
+    let r = deserialize_point(&r_bytes)?;
+    let s = Scalar52::from_bytes(&s_bytes);
+
+    let mut variants = Vec::new();
+    for n in 1..=Scalar52::max_multiple_of_l(&s) {
+        let s_prime = Scalar52::add_multiple_of_l(&s, n).unwrap();
+        let comment = if s_prime.is_below_pow2(253) {
+            format!("S + {}L", n)
+        } else {
+            format!("S + {}L, beyond the high bit checks", n)
+        };
+        variants.push((
+            [&r_bytes[..], &s_prime.to_bytes()[..]].concat(),
+            Variant::SPlusMultipleOfL,
+            comment,
+        ));
+    }
+    for (i, torsion) in EIGHT_TORSION.iter().enumerate().skip(1) {
+        let r_prime = r + deserialize_point(torsion)?;
+        variants.push((
+            [&r_prime.compress().as_bytes()[..], &s_bytes[..]].concat(),
+            Variant::RPlusTorsion,
+            format!("R + EIGHT_TORSION[{}], same S", i),
+        ));
+    }
+    for (encoding, comment) in alternative_encodings(&r_bytes) {
+        variants.push((
+            [&encoding[..], &s_bytes[..]].concat(),
+            Variant::ReencodedR,
+            format!("R re-encoded as {}", comment),
+        ));
+    }
+    Ok(variants)
+}
+
+/// An honest signature, followed by all its variants.
+pub fn malleability_vectors() -> Vec<MalleabilityVector> {
+    let mut rng = new_rng();
+    let mut seed = [0u8; 32];
+    rng.fill_bytes(&mut seed);
+    let mut message = [0u8; 32];
+    rng.fill_bytes(&mut message);
+
+    let expanded = expand_seed(&seed);
+    let pub_key = expanded.public_key();
+    let signature = expanded.sign(&message);
+
+    let mut vec = vec![MalleabilityVector {
+        comment: "honest signature".to_string(),
+        variant: Variant::Honest,
+        producer: Producer::Signer,
+        message,
+        pub_key,
+        accepted_by: accepted_by(&message, &pub_key, &signature),
+        signature: signature.clone(),
+    }];
+
+    for (variant_signature, variant, comment) in third_party_variants(&signature).unwrap() {
+        vec.push(MalleabilityVector {
+            comment,
+            variant,
+            producer: Producer::ThirdParty,
+            message,
+            pub_key,
+            accepted_by: accepted_by(&message, &pub_key, &variant_signature),
+            signature: variant_signature,
+        });
+    }
+
+    // The signer can add a torsion point T to R and recompute S for the new
+    // hash: [8](R + T) = [8]R, so cofactored verification accepts.
+    let a = expanded.scalar();
+    let r = expanded.nonce(&message);
+    let a_point = a * ED25519_BASEPOINT_POINT;
+    for (i, torsion) in EIGHT_TORSION.iter().enumerate().skip(1) {
+        let r_prime = r * ED25519_BASEPOINT_POINT + deserialize_point(torsion).unwrap();
+        let s_prime = r + compute_hram(&message, &a_point, &r_prime) * a;
+        let variant = serialize_signature(&r_prime, &s_prime);
+        vec.push(MalleabilityVector {
+            comment: format!("R + EIGHT_TORSION[{}], S recomputed", i),
+            variant: Variant::RPlusTorsionResigned,
+            producer: Producer::Signer,
+            message,
+            pub_key,
+            accepted_by: accepted_by(&message, &pub_key, &variant),
+            signature: variant,
+        });
+    }
+
+    for tv in vec.iter() {
+        debug!(
+            "{} ({:?})\n\
+             accepted by: {}\n\
+             \"message\": \"{}\", \"pub_key\": \"{}\", \"signature\": \"{}\"",
+            tv.comment,
+            tv.producer,
+            tv.accepted_by.join(", "),
+            hex::encode(&tv.message),
+            hex::encode(&tv.pub_key),
+            hex::encode(&tv.signature)
+        );
+    }
+    vec
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alternative_encodings() {
+        use crate::EIGHT_TORSION_NON_CANONICAL;
+
+        // identity: (0, 1) has a flipped sign and a y + p encoding
+        let encodings = alternative_encodings(&EIGHT_TORSION[0]);
+        assert!(encodings
+            .iter()
+            .any(|(e, _)| e == &EIGHT_TORSION_NON_CANONICAL[0]));
+        assert!(encodings
+            .iter()
+            .any(|(e, _)| e == &EIGHT_TORSION_NON_CANONICAL[3]));
+        // order 2: (0, -1) only has a flipped sign
+        let encodings = alternative_encodings(&EIGHT_TORSION[4]);
+        assert_eq!(encodings.len(), 1);
+        assert_eq!(encodings[0].0, EIGHT_TORSION_NON_CANONICAL[2]);
+        // the basepoint has none
+        let basepoint = ED25519_BASEPOINT_POINT.compress().to_bytes();
+        assert!(alternative_encodings(&basepoint).is_empty());
+    }
+
+    #[test]
+    fn test_malleability_vectors() {
+        for tv in malleability_vectors().iter() {
+            let rfc8032 = tv.accepted_by.contains(&"RFC 8032");
+            let cofactored = tv.accepted_by.contains(&"cofactored");
+            match tv.variant {
+                Variant::Honest => assert!(rfc8032, "{}", tv.comment),
+                // S + nL (third party) and R + T with a fresh S (signer only)
+                Variant::SPlusMultipleOfL | Variant::RPlusTorsionResigned => {
+                    assert!(!rfc8032 && cofactored, "{}", tv.comment)
+                }
+                Variant::RPlusTorsion | Variant::ReencodedR => {
+                    assert!(tv.accepted_by.is_empty(), "{}", tv.comment)
+                }
+            }
+            let third_party = tv.producer == Producer::ThirdParty;
+            let signer_only = matches!(tv.variant, Variant::Honest | Variant::RPlusTorsionResigned);
+            assert_eq!(third_party, !signer_only, "{}", tv.comment);
+        }
+        assert!(third_party_variants(&[0u8; 31]).is_err());
+        assert!(third_party_variants(&[0u8; 65]).is_err());
+    }
+}
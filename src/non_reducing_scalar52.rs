@@ -3,12 +3,22 @@
 // This source code is licensed under the APACHE 2.0 license found in
 // the LICENSE file in the root directory of this source tree.
 
+use core::cmp::Ordering;
 use core::ops::{Index, IndexMut};
 
-/// The `Scalar52` struct represents an element in
-/// ℤ/ℓℤ as 5 52-bit limbs.
+/// The `Scalar52` struct represents an unreduced 256-bit integer
+/// as 5 52-bit limbs (the top limb holding 48 bits).
+///
+/// Unlike curve25519-dalek's own `Scalar52`, none of the operations below
+/// reduce mod ℓ: they are plain integer arithmetic on [0, 2^256), which is
+/// what we need to build out-of-range S values. Operations that would leave
+/// that range report it, rather than silently dropping carries.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Scalar52(pub [u64; 5]);
 
+const MASK: u64 = (1u64 << 52) - 1;
+const TOP_MASK: u64 = (1u64 << 48) - 1;
+
 /// `L` is the order of base point, i.e. 2^252 + 27742317777372353535851937790883648493
 pub const L: Scalar52 = Scalar52([
     0x0002_631a_5cf5_d3ed,
@@ -33,15 +43,13 @@ impl Scalar52 {
             }
         }
 
-        let mask = (1u64 << 52) - 1;
-        let top_mask = (1u64 << 48) - 1;
         let mut s = Scalar52::zero();
 
-        s[0] = words[0] & mask;
-        s[1] = ((words[0] >> 52) | (words[1] << 12)) & mask;
-        s[2] = ((words[1] >> 40) | (words[2] << 24)) & mask;
-        s[3] = ((words[2] >> 28) | (words[3] << 36)) & mask;
-        s[4] = (words[3] >> 16) & top_mask;
+        s[0] = words[0] & MASK;
+        s[1] = ((words[0] >> 52) | (words[1] << 12)) & MASK;
+        s[2] = ((words[1] >> 40) | (words[2] << 24)) & MASK;
+        s[3] = ((words[2] >> 28) | (words[3] << 36)) & MASK;
+        s[4] = (words[3] >> 16) & TOP_MASK;
 
         s
     }
@@ -87,18 +95,135 @@ impl Scalar52 {
     }
 
     /// Compute `a + b` (without mod ℓ)
+    ///
+    /// Panics if the sum does not fit in 256 bits, see `checked_add`.
     pub fn add(a: &Scalar52, b: &Scalar52) -> Scalar52 {
+        Scalar52::checked_add(a, b).expect("Scalar52 addition overflowed 256 bits")
+    }
+
+    /// Compute `a + b mod 2^256`, along with whether the sum reached 2^256
+    pub fn overflowing_add(a: &Scalar52, b: &Scalar52) -> (Scalar52, bool) {
         let mut sum = Scalar52::zero();
-        let mask = (1u64 << 52) - 1;
 
-        // a + b
         let mut carry: u64 = 0;
         for i in 0..5 {
             carry = a[i] + b[i] + (carry >> 52);
-            sum[i] = carry & mask;
+            sum[i] = carry & MASK;
+        }
+
+        let overflow = sum[4] > TOP_MASK;
+        sum[4] &= TOP_MASK;
+        (sum, overflow)
+    }
+
+    /// Compute `a + b` (without mod ℓ), or `None` if it does not fit in 256 bits
+    pub fn checked_add(a: &Scalar52, b: &Scalar52) -> Option<Scalar52> {
+        match Scalar52::overflowing_add(a, b) {
+            (sum, false) => Some(sum),
+            (_, true) => None,
+        }
+    }
+
+    /// Compute `a - b mod 2^256`, along with whether the subtraction borrowed
+    /// (i.e. `a < b`)
+    pub fn overflowing_sub(a: &Scalar52, b: &Scalar52) -> (Scalar52, bool) {
+        let mut difference = Scalar52::zero();
+
+        let mut borrow: u64 = 0;
+        for i in 0..5 {
+            borrow = a[i].wrapping_sub(b[i] + (borrow >> 63));
+            difference[i] = borrow & MASK;
+        }
+
+        difference[4] &= TOP_MASK;
+        (difference, borrow >> 63 == 1)
+    }
+
+    /// Compute `a - b` (without mod ℓ), or `None` if `a < b`
+    pub fn checked_sub(a: &Scalar52, b: &Scalar52) -> Option<Scalar52> {
+        match Scalar52::overflowing_sub(a, b) {
+            (difference, false) => Some(difference),
+            (_, true) => None,
+        }
+    }
+
+    /// Compute `k * a mod 2^256`, along with whether the product reached 2^256
+    pub fn overflowing_mul_small(a: &Scalar52, k: u64) -> (Scalar52, bool) {
+        let mut product = Scalar52::zero();
+
+        let mut carry: u128 = 0;
+        for i in 0..5 {
+            carry = u128::from(a[i]) * u128::from(k) + (carry >> 52);
+            product[i] = (carry as u64) & MASK;
+        }
+
+        let overflow = (carry >> 52) != 0 || product[4] > TOP_MASK;
+        product[4] &= TOP_MASK;
+        (product, overflow)
+    }
+
+    /// Compute `k * a` (without mod ℓ), or `None` if it does not fit in 256 bits
+    pub fn checked_mul_small(a: &Scalar52, k: u64) -> Option<Scalar52> {
+        match Scalar52::overflowing_mul_small(a, k) {
+            (product, false) => Some(product),
+            (_, true) => None,
+        }
+    }
+
+    /// Compute `a + k * ℓ`, or `None` if it does not fit in 256 bits.
+    ///
+    /// This is the same scalar as `a` mod ℓ, which is what lets us build
+    /// signatures with a large S that still satisfy the verification equation.
+    pub fn add_multiple_of_l(a: &Scalar52, k: u64) -> Option<Scalar52> {
+        Scalar52::checked_mul_small(&L, k).and_then(|kl| Scalar52::checked_add(a, &kl))
+    }
+
+    /// The largest `k` such that `a + k * ℓ` still fits in 256 bits
+    pub fn max_multiple_of_l(a: &Scalar52) -> u64 {
+        let mut k = 0;
+        while Scalar52::add_multiple_of_l(a, k + 1).is_some() {
+            k += 1;
+        }
+        k
+    }
+
+    /// The number of significant bits of this `Scalar52`
+    pub fn bit_length(&self) -> usize {
+        for i in (0..5).rev() {
+            if self[i] != 0 {
+                return 52 * i + (64 - self[i].leading_zeros() as usize);
+            }
+        }
+        0
+    }
+
+    /// Whether this `Scalar52` is strictly lower than `2^k`
+    pub fn is_below_pow2(&self, k: usize) -> bool {
+        self.bit_length() <= k
+    }
+
+    /// Whether this `Scalar52` is the canonical representative of its class
+    /// mod ℓ, i.e. is strictly lower than ℓ
+    pub fn is_canonical(&self) -> bool {
+        *self < L
+    }
+}
+
+impl Ord for Scalar52 {
+    fn cmp(&self, other: &Scalar52) -> Ordering {
+        for i in (0..5).rev() {
+            match self[i].cmp(&other[i]) {
+                Ordering::Equal => continue,
+                ord => return ord,
+            }
         }
+        Ordering::Equal
+    }
+}
 
-        sum
+impl PartialOrd for Scalar52 {
+    fn partial_cmp(&self, other: &Scalar52) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
@@ -114,3 +239,90 @@ impl IndexMut<usize> for Scalar52 {
         &mut (self.0[_index])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigUint;
+    use proptest::prelude::*;
+
+    fn to_biguint(s: &Scalar52) -> BigUint {
+        BigUint::from_bytes_le(&s.to_bytes())
+    }
+
+    fn two_256() -> BigUint {
+        BigUint::from(1u8) << 256
+    }
+
+    fn arb_scalar52() -> impl Strategy<Value = Scalar52> {
+        any::<[u8; 32]>().prop_map(|bytes| Scalar52::from_bytes(&bytes))
+    }
+
+    #[test]
+    fn test_l_matches_reference() {
+        let l = (BigUint::from(1u8) << 252)
+            + "27742317777372353535851937790883648493"
+                .parse::<BigUint>()
+                .unwrap();
+        assert_eq!(to_biguint(&L), l);
+        assert!(!L.is_canonical());
+        assert_eq!(L.bit_length(), 253);
+        assert_eq!(Scalar52::max_multiple_of_l(&Scalar52::zero()), 15);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_bytes_roundtrip(bytes in any::<[u8; 32]>()) {
+            prop_assert_eq!(Scalar52::from_bytes(&bytes).to_bytes(), bytes);
+        }
+
+        #[test]
+        fn prop_add(a in arb_scalar52(), b in arb_scalar52()) {
+            let expected = to_biguint(&a) + to_biguint(&b);
+            let (sum, overflow) = Scalar52::overflowing_add(&a, &b);
+            prop_assert_eq!(overflow, expected >= two_256());
+            prop_assert_eq!(to_biguint(&sum), &expected % two_256());
+            prop_assert_eq!(Scalar52::checked_add(&a, &b).is_none(), overflow);
+        }
+
+        #[test]
+        fn prop_sub(a in arb_scalar52(), b in arb_scalar52()) {
+            let (difference, borrow) = Scalar52::overflowing_sub(&a, &b);
+            prop_assert_eq!(borrow, a < b);
+            let expected = (to_biguint(&a) + two_256() - to_biguint(&b)) % two_256();
+            prop_assert_eq!(to_biguint(&difference), expected);
+        }
+
+        #[test]
+        fn prop_mul_small(a in arb_scalar52(), k in any::<u64>()) {
+            let expected = to_biguint(&a) * BigUint::from(k);
+            let (product, overflow) = Scalar52::overflowing_mul_small(&a, k);
+            prop_assert_eq!(overflow, expected >= two_256());
+            prop_assert_eq!(to_biguint(&product), &expected % two_256());
+        }
+
+        #[test]
+        fn prop_cmp(a in arb_scalar52(), b in arb_scalar52()) {
+            prop_assert_eq!(a.cmp(&b), to_biguint(&a).cmp(&to_biguint(&b)));
+            prop_assert_eq!(a.is_canonical(), to_biguint(&a) < to_biguint(&L));
+        }
+
+        #[test]
+        fn prop_pow2(a in arb_scalar52(), k in 0usize..256) {
+            let pow2 = BigUint::from(1u8) << k;
+            prop_assert_eq!(a.is_below_pow2(k), to_biguint(&a) < pow2);
+            prop_assert_eq!(a.bit_length() as u64, to_biguint(&a).bits());
+        }
+
+        #[test]
+        fn prop_multiples_of_l(a in arb_scalar52()) {
+            let k = Scalar52::max_multiple_of_l(&a);
+            let shifted = Scalar52::add_multiple_of_l(&a, k);
+            prop_assert!(shifted.is_some());
+            let shifted = to_biguint(&shifted.unwrap());
+            prop_assert_eq!(&shifted % to_biguint(&L), to_biguint(&a) % to_biguint(&L));
+            prop_assert!(shifted + to_biguint(&L) >= two_256());
+            prop_assert!(Scalar52::add_multiple_of_l(&a, k + 1).is_none());
+        }
+    }
+}
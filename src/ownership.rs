@@ -0,0 +1,264 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Exclusive ownership (M-S-UEO) vectors.
+//!
+//! For small order public keys A1 and A2, a signature `(R, S)` with
+//! `R = [s]B + T` and `S = s` satisfies the cofactored equation under both,
+//! since `[8]T = [8][k]A1 = [8][k]A2 = 0`, and satisfies the cofactorless
+//! equation under both whenever `T + [k1]A1 = T + [k2]A2 = 0`. The signature
+//! then does not identify the key it was made with. The same happens for two
+//! encodings of one small order point, once they hash differently.
+//!
+//! Mixed order public keys `A1 = [a]B + T1` and `A2 = [a]B + T2` cannot
+//! share a signature: the cofactored equation under each needs
+//! `[8]S = [8](r + k1 * a) = [8](r + k2 * a)`, so `k1 = k2 mod L`, a collision
+//! of the hashes over the two distinct encodings.
+
+#[cfg(test)]
+use anyhow::anyhow;
+use anyhow::Result;
+use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT, scalar::Scalar};
+use rand::RngCore;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::malleability::alternative_encodings;
+use crate::policy::{self, Policy, REFERENCE_POLICIES};
+use crate::{deserialize_point, new_rng, serialize_signature, EIGHT_TORSION};
+
+/// One signature of `message` valid under two public keys, per `policy`.
+///
+/// `accepted_by` lists the reference policies accepting it under both keys.
+pub struct OwnershipVector {
+    pub comment: String,
+    pub policy: &'static str,
+    pub message: [u8; 32],
+    pub pub_key1: [u8; 32],
+    pub pub_key2: [u8; 32],
+    pub signature: Vec<u8>,
+    pub accepted_by: Vec<&'static str>,
+}
+
+impl Serialize for OwnershipVector {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("OwnershipVector", 7)?;
+        state.serialize_field("comment", &self.comment)?;
+        state.serialize_field("policy", &self.policy)?;
+        state.serialize_field("message", &hex::encode(&self.message))?;
+        state.serialize_field("pub_key1", &hex::encode(&self.pub_key1))?;
+        state.serialize_field("pub_key2", &hex::encode(&self.pub_key2))?;
+        state.serialize_field("signature", &hex::encode(&self.signature))?;
+        state.serialize_field("accepted_by", &self.accepted_by)?;
+        state.end()
+    }
+}
+
+impl OwnershipVector {
+    /// Check that a library binds the signature to a single public key:
+    /// `verify` returns whether the library accepts `(message, pub_key,
+    /// signature)`.
+    #[cfg(test)]
+    pub fn check_ownership<F>(&self, verify: F) -> Result<()>
+    where
+        F: Fn(&[u8], &[u8; 32], &[u8]) -> bool,
+    {
+        if verify(&self.message, &self.pub_key1, &self.signature)
+            && verify(&self.message, &self.pub_key2, &self.signature)
+        {
+            Err(anyhow!(
+                "signature {} verifies under both public keys {} and {}",
+                hex::encode(&self.signature),
+                hex::encode(&self.pub_key1),
+                hex::encode(&self.pub_key2)
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn accepts_both(
+    policy: &Policy,
+    message: &[u8],
+    pub_key1: &[u8],
+    pub_key2: &[u8],
+    signature: &[u8],
+) -> bool {
+    policy.verify(message, pub_key1, signature).is_ok()
+        && policy.verify(message, pub_key2, signature).is_ok()
+}
+
+/// Grind `s` and the torsion component T of `R = [s]B + T` until `(R, s)`
+/// verifies under both keys per `policy`, and, if `exclusive` is set, under
+/// neither of them per cofactorless verification.
+fn ownership_vector(
+    rng: &mut impl RngCore,
+    comment: String,
+    pub_key1: [u8; 32],
+    pub_key2: [u8; 32],
+    policy: &Policy,
+    exclusive: bool,
+) -> OwnershipVector {
+    let mut message = [0u8; 32];
+    rng.fill_bytes(&mut message);
+    loop {
+        let mut scalar_bytes = [0u8; 32];
+        rng.fill_bytes(&mut scalar_bytes);
+        let s = Scalar::from_bytes_mod_order(scalar_bytes);
+        let torsion = EIGHT_TORSION[(rng.next_u32() % 8) as usize];
+        let r = s * ED25519_BASEPOINT_POINT + deserialize_point(&torsion).unwrap();
+        let signature = serialize_signature(&r, &s);
+
+        if !accepts_both(policy, &message, &pub_key1, &pub_key2, &signature)
+            || (exclusive
+                && (policy::COFACTORLESS
+                    .verify(&message, &pub_key1, &signature)
+                    .is_ok()
+                    || policy::COFACTORLESS
+                        .verify(&message, &pub_key2, &signature)
+                        .is_ok()))
+        {
+            continue;
+        }
+
+        let accepted_by = REFERENCE_POLICIES
+            .iter()
+            .filter(|p| accepts_both(p, &message, &pub_key1, &pub_key2, &signature))
+            .map(|p| p.name)
+            .collect();
+        debug!(
+            "{}\n\
+             one signature for two public keys under {} verification\n\
+             \"message\": \"{}\", \"pub_key1\": \"{}\", \"pub_key2\": \"{}\", \"signature\": \"{}\"",
+            comment,
+            policy.name,
+            hex::encode(&message),
+            hex::encode(&pub_key1),
+            hex::encode(&pub_key2),
+            hex::encode(&signature)
+        );
+        return OwnershipVector {
+            comment,
+            policy: policy.name,
+            message,
+            pub_key1,
+            pub_key2,
+            signature,
+            accepted_by,
+        };
+    }
+}
+
+/// Exclusive ownership vectors.
+///
+/// For pairs of distinct small order points, the first vector verifies under
+/// both keys per cofactorless (hence also cofactored) verification, the second
+/// per cofactored verification only. For pairs of encodings of the same small
+/// order point, the vector verifies per cofactorless verification hashing the
+/// raw encodings, so that the two keys yield distinct hashes.
+pub fn ownership_vectors() -> Vec<OwnershipVector> {
+    let mut rng = new_rng();
+    let mut vec = Vec::new();
+    // indices into EIGHT_TORSION, of orders (1, 2), (2, 4), (4, 8), (8, 8)
+    for (idx1, idx2) in [(0, 4), (4, 2), (2, 1), (1, 3)].iter() {
+        for (policy, exclusive) in
+            [(&policy::COFACTORLESS, false), (&policy::COFACTORED, true)].iter()
+        {
+            vec.push(ownership_vector(
+                &mut rng,
+                format!("EIGHT_TORSION[{}] and EIGHT_TORSION[{}]", idx1, idx2),
+                EIGHT_TORSION[*idx1],
+                EIGHT_TORSION[*idx2],
+                policy,
+                *exclusive,
+            ));
+        }
+    }
+
+    let raw_hash = Policy::by_name("cofactorless, raw hash").unwrap();
+    for (idx, torsion) in EIGHT_TORSION.iter().enumerate() {
+        for (encoding, description) in alternative_encodings(torsion) {
+            vec.push(ownership_vector(
+                &mut rng,
+                format!(
+                    "EIGHT_TORSION[{}] and its encoding with {}",
+                    idx, description
+                ),
+                *torsion,
+                encoding,
+                &raw_hash,
+                false,
+            ));
+        }
+    }
+    vec
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_hram;
+    use core::ops::Neg;
+
+    fn verifier(policy: &Policy) -> impl Fn(&[u8], &[u8; 32], &[u8]) -> bool + '_ {
+        move |msg, pk, sig| policy.verify(msg, pk, sig).is_ok()
+    }
+
+    #[test]
+    fn test_ownership_vectors() {
+        let vec = ownership_vectors();
+        for tv in vec.iter() {
+            let policy = Policy::by_name(tv.policy).unwrap();
+            assert!(tv.accepted_by.contains(&tv.policy), "{}", tv.comment);
+            assert!(tv.check_ownership(verifier(&policy)).is_err());
+            // small order keys are rejected by strict verification
+            assert!(tv
+                .check_ownership(verifier(&Policy::by_name("strict").unwrap()))
+                .is_ok());
+            if tv.policy == "cofactored" {
+                assert!(tv.check_ownership(verifier(&policy::COFACTORLESS)).is_ok());
+            }
+        }
+        // two encodings of the identity, and of the points of order 2 and 4
+        assert_eq!(vec.len(), 8 + 6);
+    }
+
+    #[test]
+    fn test_mixed_order_keys_do_not_share_signatures() {
+        let mut rng = new_rng();
+        let mut scalar_bytes = [0u8; 32];
+        rng.fill_bytes(&mut scalar_bytes);
+        let a = Scalar::from_bytes_mod_order(scalar_bytes);
+        let pub_key1 = a * ED25519_BASEPOINT_POINT + deserialize_point(&EIGHT_TORSION[1]).unwrap();
+        let pub_key2 = pub_key1 + deserialize_point(&EIGHT_TORSION[4]).unwrap().neg();
+
+        for torsion in EIGHT_TORSION.iter() {
+            let mut message = [0u8; 32];
+            rng.fill_bytes(&mut message);
+            rng.fill_bytes(&mut scalar_bytes);
+            let r = Scalar::from_bytes_mod_order(scalar_bytes);
+            let big_r = r * ED25519_BASEPOINT_POINT + deserialize_point(torsion).unwrap();
+            let s = r + compute_hram(&message, &pub_key1, &big_r) * a;
+            let tv = OwnershipVector {
+                comment: String::new(),
+                policy: "cofactored",
+                message,
+                pub_key1: pub_key1.compress().to_bytes(),
+                pub_key2: pub_key2.compress().to_bytes(),
+                signature: serialize_signature(&big_r, &s),
+                accepted_by: vec![],
+            };
+            assert!(policy::COFACTORED
+                .verify(&tv.message, &tv.pub_key1, &tv.signature)
+                .is_ok());
+            for policy in REFERENCE_POLICIES.iter() {
+                assert!(tv.check_ownership(verifier(policy)).is_ok());
+            }
+        }
+    }
+}
@@ -0,0 +1,198 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Reference verification policies.
+//!
+//! Ed25519 verifiers differ along a few independent axes: the verification
+//! equation, whether S must be reduced, whether A and R must be canonically
+//! encoded or of large order, and whether R and A are hashed as received or
+//! re-encoded. A `Policy` fixes each of those, and `REFERENCE_POLICIES` lists
+//! the combinations found in practice.
+
+use anyhow::{anyhow, Result};
+use core::ops::Neg;
+use curve25519_dalek::{edwards::EdwardsPoint, scalar::Scalar, traits::IsIdentity};
+
+use crate::non_reducing_scalar52::Scalar52;
+use crate::{
+    check_slice_size, compute_hram, compute_hram_with_arrays, deserialize_point,
+    verify_final_cofactored, verify_final_cofactorless,
+};
+
+/// The verification equation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Equation {
+    /// `[8](R + kA) = [8]SB`
+    Cofactored,
+    /// `R + kA = SB`
+    Cofactorless,
+    /// `[8]R + [8k mod L]A = [8S mod L]B`
+    PreReducedCofactored,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Policy {
+    pub name: &'static str,
+    pub equation: Equation,
+    /// Reject S ≥ L
+    pub canonical_s: bool,
+    /// Reject non-canonical encodings of A and R
+    pub canonical_points: bool,
+    /// Reject A or R of small order
+    pub reject_small_order: bool,
+    /// Hash the canonical re-encodings of R and A rather than the bytes received
+    pub reserialize: bool,
+}
+
+pub const COFACTORED: Policy = Policy {
+    name: "cofactored",
+    equation: Equation::Cofactored,
+    canonical_s: false,
+    canonical_points: false,
+    reject_small_order: false,
+    reserialize: true,
+};
+
+pub const COFACTORLESS: Policy = Policy {
+    name: "cofactorless",
+    equation: Equation::Cofactorless,
+    ..COFACTORED
+};
+
+pub const REFERENCE_POLICIES: [Policy; 8] = [
+    COFACTORED,
+    COFACTORLESS,
+    Policy {
+        name: "pre-reduced cofactored",
+        equation: Equation::PreReducedCofactored,
+        ..COFACTORED
+    },
+    Policy {
+        name: "cofactored, raw hash",
+        reserialize: false,
+        ..COFACTORED
+    },
+    Policy {
+        name: "cofactorless, raw hash",
+        reserialize: false,
+        ..COFACTORLESS
+    },
+    Policy {
+        name: "RFC 8032",
+        canonical_s: true,
+        canonical_points: true,
+        reserialize: false,
+        ..COFACTORLESS
+    },
+    Policy {
+        name: "ZIP-215",
+        canonical_s: true,
+        reserialize: false,
+        ..COFACTORED
+    },
+    Policy {
+        name: "strict",
+        canonical_s: true,
+        canonical_points: true,
+        reject_small_order: true,
+        reserialize: false,
+        ..COFACTORLESS
+    },
+];
+
+impl Policy {
+    /// Verify `signature` of `message` under `pub_key` per this policy. The
+    /// error explains the first check that failed.
+    pub fn verify(&self, message: &[u8], pub_key: &[u8], signature: &[u8]) -> Result<()> {
+        let pub_key_bytes = check_slice_size(pub_key, 32, "pub_key")?;
+        let signature = check_slice_size(signature, 64, "signature")?;
+        let (r_bytes, s_bytes) = signature.split_at(32);
+
+        let a = deserialize_point(pub_key_bytes).map_err(|_| anyhow!("A does not decode"))?;
+        let r = deserialize_point(r_bytes).map_err(|_| anyhow!("R does not decode"))?;
+        if self.canonical_points {
+            if a.compress().as_bytes() != pub_key_bytes {
+                return Err(anyhow!("A is not canonically encoded"));
+            }
+            if r.compress().as_bytes() != r_bytes {
+                return Err(anyhow!("R is not canonically encoded"));
+            }
+        }
+        if self.reject_small_order {
+            if a.is_small_order() {
+                return Err(anyhow!("A has small order"));
+            }
+            if r.is_small_order() {
+                return Err(anyhow!("R has small order"));
+            }
+        }
+
+        let mut s_arr = [0u8; 32];
+        s_arr.copy_from_slice(s_bytes);
+        if self.canonical_s && !Scalar52::from_bytes(&s_arr).is_canonical() {
+            return Err(anyhow!("S is not reduced"));
+        }
+        // [S]B only depends on S mod L, for any 256-bit S
+        let s = Scalar::from_bytes_mod_order(s_arr);
+
+        let k = if self.reserialize {
+            compute_hram(message, &a, &r)
+        } else {
+            compute_hram_with_arrays(message, pub_key_bytes, r_bytes)
+        };
+        match self.equation {
+            Equation::Cofactored => verify_final_cofactored(&a, &(r, s), &k),
+            Equation::Cofactorless => verify_final_cofactorless(&a, &(r, s), &k),
+            Equation::PreReducedCofactored => {
+                // [8]R = [8k mod L](-A) + [8S mod L]B
+                let eight = Scalar::from(8u64);
+                let rprime = EdwardsPoint::vartime_double_scalar_mul_basepoint(
+                    &(eight * k),
+                    &a.neg(),
+                    &(eight * s),
+                );
+                if (r.mul_by_cofactor() - rprime).is_identity() {
+                    Ok(())
+                } else {
+                    Err(anyhow!("Invalid pre-reduced cofactored signature"))
+                }
+            }
+        }
+    }
+
+    /// Look up a reference policy by name
+    pub fn by_name(name: &str) -> Option<Policy> {
+        REFERENCE_POLICIES.iter().copied().find(|p| p.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_test_vectors;
+
+    #[test]
+    fn test_reference_policies() {
+        // one row per vector, one column per reference policy
+        let expected = [
+            "VVXVVVVX", "VVXVVVVX", "VVXVVVVX", "VVXVVVVV", "VXXVXXVX", "VXXVXXVX", "VVVVVXXX",
+            "VVVVVXXX", "VVXXXXXX", "XXXVVXVX", "VVVVXXVX", "VXVVVXVX",
+        ];
+        let vec = generate_test_vectors();
+        assert_eq!(vec.len(), expected.len());
+        for (i, (tv, row)) in vec.iter().zip(expected.iter()).enumerate() {
+            let actual: String = REFERENCE_POLICIES
+                .iter()
+                .map(
+                    |p| match p.verify(&tv.message, &tv.pub_key, &tv.signature) {
+                        Ok(()) => 'V',
+                        Err(_) => 'X',
+                    },
+                )
+                .collect();
+            assert_eq!(&actual, row, "vector #{}", i);
+        }
+    }
+}
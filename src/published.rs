@@ -0,0 +1,212 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Reproducibility of the published test vectors.
+//!
+//! The vectors depend on the RNG stream of `new_rng()` and on
+//! curve25519-dalek, so a dependency update could change `cases.json`, or any
+//! other file of `generate`, without anyone noticing. We regenerate them and
+//! compare with the published files, and point at the generator and the step
+//! that diverged.
+
+use anyhow::{anyhow, Result};
+use std::path::Path;
+
+use crate::validate::DECLARED;
+use crate::{cases, generate_test_vectors, test_vector_files, TestVector};
+
+/// Explain each difference between the `published` and `regenerated`
+/// vectors. Fields are compared in generation order: the message comes from
+/// the RNG stream, the public key from the key generation, R from the curve
+/// arithmetic, and S from the scalar arithmetic, so the first differing field
+/// is the step that diverged.
+pub fn compare(published: &[TestVector], regenerated: &[TestVector]) -> Vec<String> {
+    let mut differences = Vec::new();
+    if published.len() != regenerated.len() {
+        differences.push(format!(
+            "{} vectors published, {} regenerated",
+            published.len(),
+            regenerated.len()
+        ));
+    }
+    for (idx, (published, regenerated)) in published.iter().zip(regenerated.iter()).enumerate() {
+        let step = if published.message != regenerated.message {
+            "message: the RNG stream diverged"
+        } else if published.pub_key != regenerated.pub_key {
+            "pub_key: the key generation diverged"
+        } else if published.signature.get(..32) != regenerated.signature.get(..32) {
+            "R: the curve arithmetic diverged"
+        } else if published.signature != regenerated.signature {
+            "S: the scalar arithmetic diverged"
+        } else {
+            continue;
+        };
+        differences.push(format!(
+            "vector #{} ({}): {}",
+            idx,
+            DECLARED
+                .get(idx)
+                .map(|declared| declared.generator)
+                .unwrap_or("unknown generator"),
+            step
+        ));
+    }
+    differences
+}
+
+/// Regenerate the test vectors, and compare them with every file of
+/// `generate` as published, `read` giving the contents of a file by name.
+/// `cases.json` and `cases.txt` are compared vector by vector, the other files
+/// byte-for-byte.
+pub fn verify(read: impl Fn(&str) -> Result<String>) -> Result<()> {
+    let regenerated = generate_test_vectors();
+    let mut differences = Vec::new();
+    for (file, contents) in test_vector_files(&regenerated)? {
+        let published = match read(file) {
+            Ok(published) => published,
+            Err(e) => {
+                differences.push(format!("{}: cannot be read: {}", file, e));
+                continue;
+            }
+        };
+        let format = match file {
+            "cases.json" => "json",
+            "cases.txt" => "txt",
+            _ => {
+                if published != contents {
+                    differences.push(format!("{}: differs from the regenerated file", file));
+                }
+                continue;
+            }
+        };
+        let vectors = match cases::read(format, &published) {
+            Ok(vectors) => vectors,
+            Err(e) => {
+                differences.push(format!("{}: cannot be read: {}", file, e));
+                continue;
+            }
+        };
+        let vector_differences = compare(&vectors, &regenerated);
+        if vector_differences.is_empty() && contents != published {
+            differences.push(format!("{}: same vectors, but formatted differently", file));
+        }
+        differences.extend(
+            vector_differences
+                .iter()
+                .map(|d| format!("{}: {}", file, d)),
+        );
+    }
+    if differences.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "the published vectors differ from the regenerated ones\n  {}",
+            differences.join("\n  ")
+        ))
+    }
+}
+
+/// `verify` the files of `dir`
+pub fn verify_dir(dir: &Path) -> Result<()> {
+    verify(|file| Ok(std::fs::read_to_string(dir.join(file))?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The checked-in contents of `file`
+    fn checked_in(file: &str) -> Result<String> {
+        let contents = match file {
+            "cases.json" => include_str!("../cases.json"),
+            "cases.txt" => include_str!("../cases.txt"),
+            "keygen_cases.json" => include_str!("../keygen_cases.json"),
+            "signing_cases.json" => include_str!("../signing_cases.json"),
+            "repudiation_cases.json" => include_str!("../repudiation_cases.json"),
+            "repudiation_cases.txt" => include_str!("../repudiation_cases.txt"),
+            "key_substitution_cases.json" => include_str!("../key_substitution_cases.json"),
+            "malleability_cases.json" => include_str!("../malleability_cases.json"),
+            "ownership_cases.json" => include_str!("../ownership_cases.json"),
+            "ownership_cases.txt" => include_str!("../ownership_cases.txt"),
+            "grid_cases.json" => include_str!("../grid_cases.json"),
+            _ => return Err(anyhow!("{} is not checked in", file)),
+        };
+        Ok(contents.to_string())
+    }
+
+    /// The checked-in files, but `file` replaced with `contents`
+    fn replacing(file: &'static str, contents: String) -> impl Fn(&str) -> Result<String> {
+        move |name| {
+            if name == file {
+                Ok(contents.clone())
+            } else {
+                checked_in(name)
+            }
+        }
+    }
+
+    #[test]
+    fn test_published_vectors_are_reproducible() {
+        verify(checked_in).unwrap();
+    }
+
+    #[test]
+    fn test_compare() {
+        let vec = generate_test_vectors();
+        assert!(compare(&vec, &vec).is_empty());
+
+        let mut changed = vec.clone();
+        changed[4].message[0] ^= 1;
+        changed[6].signature[63] ^= 1;
+        changed[9].signature[0] ^= 1;
+        changed[11].pub_key[0] ^= 1;
+        assert_eq!(
+            compare(&changed, &vec),
+            vec![
+                "vector #4 (non_zero_mixed_mixed): message: the RNG stream diverged",
+                "vector #6 (large_s): S: the scalar arithmetic diverged",
+                "vector #9 (non_zero_small_non_canonical_mixed): R: the curve arithmetic diverged",
+                "vector #11 (non_zero_mixed_small_non_canonical): pub_key: the key generation diverged",
+            ]
+        );
+        assert_eq!(compare(&vec[..11], &vec).len(), 1);
+
+        let json = cases::write("json", &changed).unwrap();
+        let err = verify(replacing("cases.json", json))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("cases.json: vector #4"), "{}", err);
+        assert!(!err.contains("cases.txt"), "{}", err);
+        let spaced = include_str!("../cases.json").replace(",", ", ");
+        let err = verify(replacing("cases.json", spaced))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("formatted differently"), "{}", err);
+    }
+
+    #[test]
+    fn test_verify_every_file() {
+        let grid = include_str!("../grid_cases.json").replacen("0", "1", 1);
+        let err = verify(replacing("grid_cases.json", grid))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("grid_cases.json: differs"), "{}", err);
+        assert!(!err.contains("\n  cases.json:"), "{}", err);
+        let err = verify(|file| {
+            if file == "ownership_cases.txt" {
+                Err(anyhow!("missing"))
+            } else {
+                checked_in(file)
+            }
+        })
+        .unwrap_err()
+        .to_string();
+        assert!(
+            err.contains("ownership_cases.txt: cannot be read"),
+            "{}",
+            err
+        );
+    }
+}
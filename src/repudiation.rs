@@ -0,0 +1,209 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Non-repudiation (message binding) vectors.
+//!
+//! With a small order public key A, a signature `(R, S)` with `R = [s]B - A`
+//! and `S = s` satisfies `R + [k]A = [S]B` whenever `[k]A = A`, i.e. whenever
+//! `k = 1 mod ord(A)`, and always satisfies the cofactored equation. The same
+//! signature then verifies for two different messages, so the signer can
+//! later deny which one they meant.
+
+#[cfg(test)]
+use anyhow::anyhow;
+use anyhow::Result;
+use core::ops::Neg;
+use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT, scalar::Scalar};
+use rand::RngCore;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::policy::{self, Equation, Policy, REFERENCE_POLICIES};
+use crate::{deserialize_point, new_rng, serialize_signature, EIGHT_TORSION};
+
+const MESSAGE1: &[u8] = b"Send 100 USD to Alice";
+const MESSAGE2: &[u8] = b"Send 100000 USD to Alice";
+
+/// One signature valid for two messages under `policy`.
+///
+/// `accepted_by` lists the reference policies accepting both messages.
+pub struct RepudiationVector {
+    pub torsion_order: u8,
+    pub policy: &'static str,
+    pub pub_key: [u8; 32],
+    pub signature: Vec<u8>,
+    pub message1: &'static [u8],
+    pub message2: &'static [u8],
+    pub accepted_by: Vec<&'static str>,
+}
+
+impl Serialize for RepudiationVector {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("RepudiationVector", 7)?;
+        state.serialize_field("torsion_order", &self.torsion_order)?;
+        state.serialize_field("policy", &self.policy)?;
+        state.serialize_field("pub_key", &hex::encode(&self.pub_key))?;
+        state.serialize_field("signature", &hex::encode(&self.signature))?;
+        state.serialize_field("message1", &hex::encode(&self.message1))?;
+        state.serialize_field("message2", &hex::encode(&self.message2))?;
+        state.serialize_field("accepted_by", &self.accepted_by)?;
+        state.end()
+    }
+}
+
+impl RepudiationVector {
+    /// Check that a library binds the signature to a single message: `verify`
+    /// returns whether the library accepts `(message, pub_key, signature)`.
+    #[cfg(test)]
+    pub fn check_binding<F>(&self, verify: F) -> Result<()>
+    where
+        F: Fn(&[u8], &[u8; 32], &[u8]) -> bool,
+    {
+        if verify(self.message1, &self.pub_key, &self.signature)
+            && verify(self.message2, &self.pub_key, &self.signature)
+        {
+            Err(anyhow!(
+                "signature {} verifies for both messages under order {} public key {}",
+                hex::encode(&self.signature),
+                self.torsion_order,
+                hex::encode(&self.pub_key)
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn accepts_both(policy: &Policy, pub_key: &[u8], signature: &[u8]) -> bool {
+    policy.verify(MESSAGE1, pub_key, signature).is_ok()
+        && policy.verify(MESSAGE2, pub_key, signature).is_ok()
+}
+
+/// Grind `s` until the signature `(R = [s]B - A, s)` verifies for both
+/// messages under `policy`, and, if `exclusive` is set, for neither of them
+/// under cofactorless verification.
+fn repudiation_vector(
+    rng: &mut impl RngCore,
+    torsion_idx: usize,
+    torsion_order: u8,
+    policy: &Policy,
+    exclusive: bool,
+) -> RepudiationVector {
+    let pub_key = deserialize_point(&EIGHT_TORSION[torsion_idx]).unwrap();
+    let pub_key_bytes = pub_key.compress().to_bytes();
+    loop {
+        let mut scalar_bytes = [0u8; 32];
+        rng.fill_bytes(&mut scalar_bytes);
+        let s = Scalar::from_bytes_mod_order(scalar_bytes);
+        let r = s * ED25519_BASEPOINT_POINT + pub_key.neg();
+        let signature = serialize_signature(&r, &s);
+
+        if !accepts_both(policy, &pub_key_bytes, &signature)
+            || (exclusive
+                && (policy::COFACTORLESS
+                    .verify(MESSAGE1, &pub_key_bytes, &signature)
+                    .is_ok()
+                    || policy::COFACTORLESS
+                        .verify(MESSAGE2, &pub_key_bytes, &signature)
+                        .is_ok()))
+        {
+            continue;
+        }
+
+        let accepted_by = REFERENCE_POLICIES
+            .iter()
+            .filter(|p| accepts_both(p, &pub_key_bytes, &signature))
+            .map(|p| p.name)
+            .collect();
+        debug!(
+            "S > 0, small A of order {}, mixed R\n\
+             one signature for two messages under {} verification\n\
+             \"pub_key\": \"{}\", \"signature\": \"{}\"",
+            torsion_order,
+            policy.name,
+            hex::encode(&pub_key_bytes),
+            hex::encode(&signature)
+        );
+        return RepudiationVector {
+            torsion_order,
+            policy: policy.name,
+            pub_key: pub_key_bytes,
+            signature,
+            message1: MESSAGE1,
+            message2: MESSAGE2,
+            accepted_by,
+        };
+    }
+}
+
+/// Non-repudiation vectors, for public keys of each torsion order and each
+/// reference policy.
+///
+/// Each vector verifies both messages under its policy. Under policies with
+/// a cofactored equation, and for a public key other than the identity, it
+/// verifies neither of them under cofactorless verification, so that it only
+/// fails libraries with that equation. Policies rejecting small order public
+/// keys, i.e. strict, cannot be fooled, and get no vector.
+pub fn repudiation_vectors() -> Vec<RepudiationVector> {
+    let mut rng = new_rng();
+    let mut vec = Vec::new();
+    // indices into EIGHT_TORSION of points of order 1, 2, 4 and 8
+    for (torsion_idx, torsion_order) in [(0, 1), (4, 2), (2, 4), (1, 8)].iter() {
+        for policy in REFERENCE_POLICIES.iter() {
+            if policy.reject_small_order {
+                continue;
+            }
+            // the identity satisfies the cofactorless equation for any k
+            let exclusive = policy.equation != Equation::Cofactorless && *torsion_order > 1;
+            vec.push(repudiation_vector(
+                &mut rng,
+                *torsion_idx,
+                *torsion_order,
+                policy,
+                exclusive,
+            ));
+        }
+    }
+    vec
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repudiation_vectors() {
+        let vec = repudiation_vectors();
+        for torsion_order in [1, 2, 4, 8].iter() {
+            let policies: Vec<&str> = vec
+                .iter()
+                .filter(|tv| tv.torsion_order == *torsion_order)
+                .map(|tv| tv.policy)
+                .collect();
+            let feasible: Vec<&str> = REFERENCE_POLICIES
+                .iter()
+                .filter(|p| !p.reject_small_order)
+                .map(|p| p.name)
+                .collect();
+            assert_eq!(policies, feasible);
+        }
+        for tv in vec.iter() {
+            assert!(tv.accepted_by.contains(&tv.policy));
+            assert!(!tv.accepted_by.contains(&"strict"));
+            let cofactorless = |msg: &[u8], pk: &[u8; 32], sig: &[u8]| {
+                policy::COFACTORLESS.verify(msg, pk, sig).is_ok()
+            };
+            assert_eq!(
+                tv.check_binding(cofactorless).is_ok(),
+                !tv.accepted_by.contains(&"cofactorless"),
+            );
+            if tv.torsion_order > 1 && tv.policy == "cofactored" {
+                assert!(tv.check_binding(cofactorless).is_ok());
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Result matrices: which library accepts which test vector.
+//!
+//! `results.md` also holds rows for the libraries tested by the scripts in
+//! `scripts/`. Running the Rust libraries only replaces their own rows, and
+//! keeps the others as they are.
+
+use anyhow::{anyhow, Result};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::Path;
+
+use crate::expected;
+use crate::libraries::{Library, RUST_LIBRARIES};
+use crate::{generate_test_vectors, TestVector};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResultRow {
+    pub library: String,
+    pub accepted: Vec<bool>,
+}
+
+impl Serialize for ResultRow {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("ResultRow", 2)?;
+        state.serialize_field("library", &self.library)?;
+        state.serialize_field("accepted", &self.accepted)?;
+        state.end()
+    }
+}
+
+impl ResultRow {
+    /// Run `library` over `vectors`
+    pub fn run(library: &Library, vectors: &[TestVector]) -> ResultRow {
+        ResultRow {
+            library: library.name.to_string(),
+            accepted: vectors
+                .iter()
+                .map(|tv| (library.verify)(&tv.message, &tv.pub_key, &tv.signature))
+                .collect(),
+        }
+    }
+
+    /// The results as `V` (accepted) and `X` (rejected), one per vector
+    pub fn to_row_string(&self) -> String {
+        self.accepted
+            .iter()
+            .map(|accepted| if *accepted { 'V' } else { 'X' })
+            .collect()
+    }
+
+    /// The row as in `results.md`, without a trailing newline
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("|{:<15}|", self.library);
+        for accepted in self.accepted.iter() {
+            out.push_str(if *accepted { " V |" } else { " X |" });
+        }
+        out
+    }
+}
+
+/// Result rows, sorted by library name ignoring case, as `results.md` is
+#[derive(Default)]
+pub struct ResultMatrix {
+    pub rows: Vec<ResultRow>,
+}
+
+impl ResultMatrix {
+    /// Add `row`, replacing any row for the same library
+    pub fn insert(&mut self, row: ResultRow) {
+        self.rows.retain(|r| r.library != row.library);
+        let key = row.library.to_lowercase();
+        let idx = self
+            .rows
+            .iter()
+            .position(|r| r.library.to_lowercase() > key)
+            .unwrap_or(self.rows.len());
+        self.rows.insert(idx, row);
+    }
+
+    /// Parse the rows of a markdown table such as `results.md`
+    pub fn from_markdown(markdown: &str) -> Result<ResultMatrix> {
+        let mut matrix = ResultMatrix::default();
+        for line in markdown.lines().filter(|line| line.starts_with('|')) {
+            let mut cells = line.trim_matches('|').split('|').map(str::trim);
+            let library = cells.next().unwrap_or_default().to_string();
+            let accepted = cells
+                .map(|cell| match cell {
+                    "V" => Ok(true),
+                    "X" => Ok(false),
+                    _ => Err(anyhow!("invalid result {:?} for {}", cell, library)),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            matrix.insert(ResultRow { library, accepted });
+        }
+        Ok(matrix)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        for row in self.rows.iter() {
+            out.push_str(&row.to_markdown());
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn to_csv(&self) -> String {
+        let vectors = self
+            .rows
+            .iter()
+            .map(|r| r.accepted.len())
+            .max()
+            .unwrap_or(0);
+        let mut out = String::from("library");
+        for i in 0..vectors {
+            out.push_str(&format!(",{}", i));
+        }
+        out.push('\n');
+        for row in self.rows.iter() {
+            out.push_str(&row.library);
+            for accepted in row.accepted.iter() {
+                out.push_str(if *accepted { ",V" } else { ",X" });
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Add `rows` to the results in `dir`: update `results.md`, keeping the rows
+/// of other libraries, and write the whole matrix to `results.json` and
+/// `results.csv`. The rows are recorded even if they differ from their
+/// expected results, but that is then an error, listing every change.
+pub fn update_results(dir: &Path, rows: Vec<ResultRow>) -> Result<ResultMatrix> {
+    let mut matrix = match std::fs::read_to_string(dir.join("results.md")) {
+        Ok(markdown) => ResultMatrix::from_markdown(&markdown)?,
+        Err(_) => ResultMatrix::default(),
+    };
+    let mut changes = Vec::new();
+    for row in rows {
+        if expected::expected_row(&row.library).is_some() {
+            if let Err(e) = expected::check_row(&row.library, &row.to_row_string()) {
+                changes.push(e.to_string());
+            }
+        }
+        matrix.insert(row);
+    }
+
+    let mut file = File::create(dir.join("results.md"))?;
+    file.write_all(matrix.to_markdown().as_bytes())?;
+    let mut file = File::create(dir.join("results.json"))?;
+    file.write_all(serde_json::to_string(&matrix.rows)?.as_bytes())?;
+    let mut file = File::create(dir.join("results.csv"))?;
+    file.write_all(matrix.to_csv().as_bytes())?;
+    if changes.is_empty() {
+        Ok(matrix)
+    } else {
+        Err(anyhow!("{}", changes.join("\n")))
+    }
+}
+
+/// Run the Rust libraries over the test vectors, and add their rows to the
+/// results in `dir`.
+pub fn write_results(dir: &Path) -> Result<Vec<ResultRow>> {
+    let vec = generate_test_vectors();
+    let rows: Vec<ResultRow> = RUST_LIBRARIES
+        .iter()
+        .map(|library| ResultRow::run(library, &vec))
+        .collect();
+    update_results(dir, rows.iter().map(ResultRow::clone).collect())?;
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_round_trip() {
+        let markdown = include_str!("../results.md");
+        let matrix = ResultMatrix::from_markdown(markdown).unwrap();
+        assert_eq!(matrix.rows.len(), expected::manifest().len());
+        assert_eq!(matrix.to_markdown(), markdown);
+    }
+
+    #[test]
+    fn test_insert_replaces_and_sorts() {
+        let mut matrix = ResultMatrix::from_markdown(include_str!("../results.md")).unwrap();
+        let len = matrix.rows.len();
+        matrix.insert(ResultRow {
+            library: "dalek".to_string(),
+            accepted: vec![true],
+        });
+        matrix.insert(ResultRow {
+            library: "Dalek".to_string(),
+            accepted: vec![false],
+        });
+        assert_eq!(matrix.rows.len(), len + 1);
+        let names: Vec<String> = matrix
+            .rows
+            .iter()
+            .map(|r| r.library.to_lowercase())
+            .collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+        assert!(matrix
+            .rows
+            .iter()
+            .any(|r| r.library == "Dalek" && r.accepted == vec![false]));
+        assert!(matrix
+            .to_csv()
+            .starts_with("library,0,1,2,3,4,5,6,7,8,9,10,11\n"));
+    }
+
+    #[test]
+    fn test_update_results_fails_on_changes() {
+        let dir = std::env::temp_dir().join(format!("speccheck-results-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("results.md"), include_str!("../results.md")).unwrap();
+        let row = |expected: &str| ResultRow {
+            library: "BouncyCastle".to_string(),
+            accepted: expected.chars().map(|c| c == 'V').collect(),
+        };
+
+        assert!(update_results(&dir, vec![row("VVVVXXXXXXXX")]).is_ok());
+        let err = update_results(&dir, vec![row("VVVVXXXXXXXV")])
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string().contains("BouncyCastle changed behavior"),
+            "{}",
+            err
+        );
+        // the new row is recorded all the same
+        let markdown = std::fs::read_to_string(dir.join("results.md")).unwrap();
+        assert!(ResultMatrix::from_markdown(&markdown)
+            .unwrap()
+            .rows
+            .contains(&row("VVVVXXXXXXXV")));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
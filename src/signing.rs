@@ -0,0 +1,690 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Reference key generation and signing, following RFC 8032 §5.1.5-5.1.6.
+//!
+//! The verification vectors in `main.rs` only exercise the verifier. The
+//! functions below derive keys from a seed the way a signer must, so that we
+//! can also produce vectors checking a library's key generation and `sign`
+//! paths byte-for-byte.
+
+use anyhow::{anyhow, Result};
+use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT, scalar::Scalar};
+use rand::RngCore;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use sha2::{Digest, Sha512};
+use std::fmt;
+
+use crate::{
+    check_slice_size, compute_hram, compute_hram_with_pk_array, deserialize_point,
+    deserialize_signature, new_rng, pick_small_nonzero_point, serialize_signature,
+    verify_cofactorless, EIGHT_TORSION,
+};
+
+/// The secret material derived from a 32-byte seed: `SHA-512(seed)` split
+/// into the (yet unclamped) secret scalar and the nonce prefix.
+pub struct ExpandedSecretKey {
+    pub raw_scalar: [u8; 32],
+    pub prefix: [u8; 32],
+}
+
+fn hash_to_scalar(chunks: &[&[u8]]) -> Scalar {
+    let mut h = Sha512::default();
+    for chunk in chunks {
+        h.update(chunk);
+    }
+    let mut output = [0u8; 64];
+    output.copy_from_slice(h.finalize().as_slice());
+    Scalar::from_bytes_mod_order_wide(&output)
+}
+
+/// Hash a seed into its expanded secret key, without clamping
+pub fn expand_seed(seed: &[u8; 32]) -> ExpandedSecretKey {
+    let mut h = [0u8; 64];
+    h.copy_from_slice(Sha512::digest(seed).as_slice());
+
+    let mut raw_scalar = [0u8; 32];
+    let mut prefix = [0u8; 32];
+    raw_scalar.copy_from_slice(&h[..32]);
+    prefix.copy_from_slice(&h[32..]);
+    ExpandedSecretKey { raw_scalar, prefix }
+}
+
+/// Clear bits 0-2, clear bit 255 and set bit 254 of a secret scalar
+pub fn clamp(raw_scalar: &[u8; 32]) -> [u8; 32] {
+    let mut scalar = *raw_scalar;
+    scalar[0] &= 248;
+    scalar[31] &= 127;
+    scalar[31] |= 64;
+    scalar
+}
+
+impl ExpandedSecretKey {
+    /// The clamped secret scalar `a`, as an (unreduced) integer
+    pub fn scalar(&self) -> Scalar {
+        // a clamped scalar is below 2^255, which `from_bits` represents as-is
+        Scalar::from_bits(clamp(&self.raw_scalar))
+    }
+
+    /// The public key `A = [a]B`
+    pub fn public_key(&self) -> [u8; 32] {
+        (self.scalar() * ED25519_BASEPOINT_POINT)
+            .compress()
+            .to_bytes()
+    }
+
+    /// The deterministic nonce `r = SHA-512(prefix || M) mod L`
+    pub fn nonce(&self, message: &[u8]) -> Scalar {
+        hash_to_scalar(&[&self.prefix, message])
+    }
+
+    /// Sign `message` with the secret scalar `a`, i.e. return `R || S` with
+    /// `R = [r]B` and `S = r + k * a mod L`
+    pub fn sign_with_scalar(&self, a: &Scalar, message: &[u8]) -> Vec<u8> {
+        let r = self.nonce(message);
+        let big_r = r * ED25519_BASEPOINT_POINT;
+        let pub_key = a * ED25519_BASEPOINT_POINT;
+        let s = r + compute_hram(message, &pub_key, &big_r) * a;
+        serialize_signature(&big_r, &s)
+    }
+
+    /// Sign `message` per RFC 8032
+    pub fn sign(&self, message: &[u8]) -> Vec<u8> {
+        self.sign_with_scalar(&self.scalar(), message)
+    }
+
+    /// Sign `message`, hashing the caller-supplied `pub_key` bytes rather
+    /// than the public key derived from the secret.
+    ///
+    /// This is the flawed API some libraries expose, e.g. `sign(sk, pk, msg)`
+    /// with `pk` taken on trust: the nonce only depends on the message, so two
+    /// signatures for distinct public keys share R, and leak `a`
+    /// (see `recover_from_double_public_key`).
+    pub fn sign_with_public_key(&self, pub_key: &[u8; 32], message: &[u8]) -> Vec<u8> {
+        let r = self.nonce(message);
+        let big_r = r * ED25519_BASEPOINT_POINT;
+        let s = r + compute_hram_with_pk_array(message, pub_key, &big_r) * self.scalar();
+        serialize_signature(&big_r, &s)
+    }
+
+    /// Sign `message` for a caller-supplied `pub_key`, refusing to do so if
+    /// it is not the public key of this secret, as a correct signer must
+    pub fn sign_checked(&self, pub_key: &[u8; 32], message: &[u8]) -> Result<Vec<u8>> {
+        if pub_key != &self.public_key() {
+            return Err(anyhow!(
+                "supplied public key {} does not match the secret key",
+                hex::encode(pub_key)
+            ));
+        }
+        Ok(self.sign(message))
+    }
+}
+
+//////////////////////////////////
+// Key generation and clamping  //
+//////////////////////////////////
+
+/// A seed → expanded key → public key → signature chain.
+///
+/// `unclamped_pub_key` and `unclamped_signature` are what a signer would
+/// output if it used the raw scalar `SHA-512(seed)[..32]` without clamping,
+/// e.g. when it is handed an externally expanded key.
+pub struct KeygenVector {
+    pub comment: &'static str,
+    pub seed: [u8; 32],
+    pub expanded_key: [u8; 64],
+    pub scalar: [u8; 32],
+    pub pub_key: [u8; 32],
+    pub message: [u8; 32],
+    pub signature: Vec<u8>,
+    pub unclamped_pub_key: [u8; 32],
+    pub unclamped_signature: Vec<u8>,
+}
+
+impl Serialize for KeygenVector {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("KeygenVector", 9)?;
+        state.serialize_field("comment", &self.comment)?;
+        state.serialize_field("seed", &hex::encode(&self.seed))?;
+        state.serialize_field("expanded_key", &hex::encode(&self.expanded_key[..]))?;
+        state.serialize_field("scalar", &hex::encode(&self.scalar))?;
+        state.serialize_field("pub_key", &hex::encode(&self.pub_key))?;
+        state.serialize_field("message", &hex::encode(&self.message))?;
+        state.serialize_field("signature", &hex::encode(&self.signature))?;
+        state.serialize_field("unclamped_pub_key", &hex::encode(&self.unclamped_pub_key))?;
+        state.serialize_field(
+            "unclamped_signature",
+            &hex::encode(&self.unclamped_signature),
+        )?;
+        state.end()
+    }
+}
+
+fn keygen_vector(
+    rng: &mut impl RngCore,
+    comment: &'static str,
+    low_bits: u8,
+    high_bits: u8,
+) -> KeygenVector {
+    // grind a seed whose raw scalar has bits 0-2 equal to `low_bits` and
+    // bits 254-255 equal to `high_bits`
+    let mut seed = [0u8; 32];
+    rng.fill_bytes(&mut seed);
+    while expand_seed(&seed).raw_scalar[0] & 7 != low_bits
+        || expand_seed(&seed).raw_scalar[31] & 192 != high_bits
+    {
+        rng.fill_bytes(&mut seed);
+    }
+    let expanded = expand_seed(&seed);
+
+    let mut message = [0u8; 32];
+    rng.fill_bytes(&mut message);
+
+    let unclamped_scalar = Scalar::from_bytes_mod_order(expanded.raw_scalar);
+    let mut expanded_key = [0u8; 64];
+    expanded_key[..32].copy_from_slice(&expanded.raw_scalar);
+    expanded_key[32..].copy_from_slice(&expanded.prefix);
+
+    let tv = KeygenVector {
+        comment,
+        seed,
+        expanded_key,
+        scalar: clamp(&expanded.raw_scalar),
+        pub_key: expanded.public_key(),
+        message,
+        signature: expanded.sign(&message),
+        unclamped_pub_key: (unclamped_scalar * ED25519_BASEPOINT_POINT)
+            .compress()
+            .to_bytes(),
+        unclamped_signature: expanded.sign_with_scalar(&unclamped_scalar, &message),
+    };
+    debug!(
+        "{}\n\
+         \"seed\": \"{}\", \"pub_key\": \"{}\", \"message\": \"{}\", \"signature\": \"{}\"",
+        comment,
+        hex::encode(&tv.seed),
+        hex::encode(&tv.pub_key),
+        hex::encode(&tv.message),
+        hex::encode(&tv.signature)
+    );
+    tv
+}
+
+/// Key generation vectors, one per configuration of the raw scalar bits
+/// that clamping overwrites. In every vector but the first, clamping changes
+/// the scalar, so a signer that skips it produces `unclamped_pub_key` and
+/// `unclamped_signature` instead of `pub_key` and `signature`.
+pub fn keygen_clamping_vectors() -> Vec<KeygenVector> {
+    let mut rng = new_rng();
+    vec![
+        keygen_vector(&mut rng, "raw scalar already clamped", 0, 64),
+        keygen_vector(&mut rng, "bits 0-2 set", 7, 64),
+        keygen_vector(&mut rng, "bit 254 clear", 0, 0),
+        keygen_vector(&mut rng, "bit 255 set", 0, 192),
+        keygen_vector(&mut rng, "all edge bits set", 7, 192),
+        keygen_vector(&mut rng, "every edge bit changed by clamping", 7, 128),
+    ]
+}
+
+////////////////////////////////
+// Deterministic signing      //
+////////////////////////////////
+
+/// The RFC 8032 §7.1 Ed25519 test vectors, as (name, secret key, public key,
+/// message, signature) hex strings
+const RFC8032_VECTORS: [(&str, &str, &str, &str, &str); 5] = [
+    (
+        "TEST 1",
+        "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+        "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        "",
+        "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+    ),
+    (
+        "TEST 2",
+        "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+        "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+        "72",
+        "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+    ),
+    (
+        "TEST 3",
+        "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+        "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+        "af82",
+        "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+    ),
+    (
+        "TEST 1024",
+        "f5e5767cf153319517630f226876b86c8160cc583bc013744c6bf255f5cc0ee5",
+        "278117fc144c72340f67d0f2316e8386ceffbf2b2428c9c51fef7c597f1d426e",
+        "08b8b2b733424243760fe426a4b54908632110a66c2f6591eabd3345e3e4eb98fa6e264bf09efe12ee50f8f54e9f77b1e355f6c50544e23fb1433ddf73be84d879de7c0046dc4996d9e773f4bc9efe5738829adb26c81b37c93a1b270b20329d658675fc6ea534e0810a4432826bf58c941efb65d57a338bbd2e26640f89ffbc1a858efcb8550ee3a5e1998bd177e93a7363c344fe6b199ee5d02e82d522c4feba15452f80288a821a579116ec6dad2b3b310da903401aa62100ab5d1a36553e06203b33890cc9b832f79ef80560ccb9a39ce767967ed628c6ad573cb116dbefefd75499da96bd68a8a97b928a8bbc103b6621fcde2beca1231d206be6cd9ec7aff6f6c94fcd7204ed3455c68c83f4a41da4af2b74ef5c53f1d8ac70bdcb7ed185ce81bd84359d44254d95629e9855a94a7c1958d1f8ada5d0532ed8a5aa3fb2d17ba70eb6248e594e1a2297acbbb39d502f1a8c6eb6f1ce22b3de1a1f40cc24554119a831a9aad6079cad88425de6bde1a9187ebb6092cf67bf2b13fd65f27088d78b7e883c8759d2c4f5c65adb7553878ad575f9fad878e80a0c9ba63bcbcc2732e69485bbc9c90bfbd62481d9089beccf80cfe2df16a2cf65bd92dd597b0707e0917af48bbb75fed413d238f5555a7a569d80c3414a8d0859dc65a46128bab27af87a71314f318c782b23ebfe808b82b0ce26401d2e22f04d83d1255dc51addd3b75a2b1ae0784504df543af8969be3ea7082ff7fc9888c144da2af58429ec96031dbcad3dad9af0dcbaaaf268cb8fcffead94f3c7ca495e056a9b47acdb751fb73e666c6c655ade8297297d07ad1ba5e43f1bca32301651339e22904cc8c42f58c30c04aafdb038dda0847dd988dcda6f3bfd15c4b4c4525004aa06eeff8ca61783aacec57fb3d1f92b0fe2fd1a85f6724517b65e614ad6808d6f6ee34dff7310fdc82aebfd904b01e1dc54b2927094b2db68d6f903b68401adebf5a7e08d78ff4ef5d63653a65040cf9bfd4aca7984a74d37145986780fc0b16ac451649de6188a7dbdf191f64b5fc5e2ab47b57f7f7276cd419c17a3ca8e1b939ae49e488acba6b965610b5480109c8b17b80e1b7b750dfc7598d5d5011fd2dcc5600a32ef5b52a1ecc820e308aa342721aac0943bf6686b64b2579376504ccc493d97e6aed3fb0f9cd71a43dd497f01f17c0e2cb3797aa2a2f256656168e6c496afc5fb93246f6b1116398a346f1a641f3b041e989f7914f90cc2c7fff357876e506b50d334ba77c225bc307ba537152f3f1610e4eafe595f6d9d90d11faa933a15ef1369546868a7f3a45a96768d40fd9d03412c091c6315cf4fde7cb68606937380db2eaaa707b4c4185c32eddcdd306705e4dc1ffc872eeee475a64dfac86aba41c0618983f8741c5ef68d3a101e8a3b8cac60c905c15fc910840b94c00a0b9d0",
+        "0aab4c900501b3e24d7cdf4663326a3a87df5e4843b2cbdb67cbf6e460fec350aa5371b1508f9f4528ecea23c436d94b5e8fcd4f681e30a6ac00a9704a188a03",
+    ),
+    (
+        "TEST SHA(abc)",
+        "833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42",
+        "ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf",
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+        "dc2a4459e7369633a52b1bf277839a00201009a3efbf3ecb69bea2186c26b58909351fc9ac90b3ecfdfbc7c66431e0303dca179c138ac17ad9bef1177331a704",
+    ),
+];
+
+/// A seed, message and the unique RFC 8032 signature of that message
+pub struct SigningVector {
+    pub comment: &'static str,
+    pub seed: [u8; 32],
+    pub pub_key: [u8; 32],
+    pub message: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl Serialize for SigningVector {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("SigningVector", 5)?;
+        state.serialize_field("comment", &self.comment)?;
+        state.serialize_field("seed", &hex::encode(&self.seed))?;
+        state.serialize_field("pub_key", &hex::encode(&self.pub_key))?;
+        state.serialize_field("message", &hex::encode(&self.message))?;
+        state.serialize_field("signature", &hex::encode(&self.signature))?;
+        state.end()
+    }
+}
+
+fn decode_seed(seed: &[u8]) -> Result<[u8; 32]> {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(check_slice_size(seed, 32, "seed")?);
+    Ok(bytes)
+}
+
+/// Signing vectors: the RFC 8032 §7.1 vectors, followed by vectors for
+/// messages of every length a signer might special-case.
+///
+/// Ed25519 signing is deterministic, so a conforming `sign` must reproduce
+/// each `signature` byte-for-byte.
+pub fn signing_vectors() -> Vec<SigningVector> {
+    let mut vec = Vec::new();
+    for (name, seed, pub_key, message, signature) in RFC8032_VECTORS.iter() {
+        let seed = decode_seed(&hex::decode(seed).unwrap()).unwrap();
+        let mut pub_key_bytes = [0u8; 32];
+        pub_key_bytes.copy_from_slice(&hex::decode(pub_key).unwrap());
+        vec.push(SigningVector {
+            comment: name,
+            seed,
+            pub_key: pub_key_bytes,
+            message: hex::decode(message).unwrap(),
+            signature: hex::decode(signature).unwrap(),
+        });
+    }
+
+    let mut rng = new_rng();
+    for (comment, len) in [
+        ("empty message", 0),
+        ("1-byte message", 1),
+        ("32-byte message", 32),
+        ("64-byte message", 64),
+        ("127-byte message", 127),
+        ("128-byte message", 128),
+        ("1024-byte message", 1024),
+    ]
+    .iter()
+    {
+        let mut seed = [0u8; 32];
+        rng.fill_bytes(&mut seed);
+        let mut message = vec![0u8; *len];
+        rng.fill_bytes(&mut message);
+
+        let expanded = expand_seed(&seed);
+        let tv = SigningVector {
+            comment,
+            seed,
+            pub_key: expanded.public_key(),
+            signature: expanded.sign(&message),
+            message,
+        };
+        debug!(
+            "{}\n\
+             \"seed\": \"{}\", \"pub_key\": \"{}\", \"signature\": \"{}\"",
+            comment,
+            hex::encode(&tv.seed),
+            hex::encode(&tv.pub_key),
+            hex::encode(&tv.signature)
+        );
+        vec.push(tv);
+    }
+    vec
+}
+
+////////////////////////////////
+// Double public key oracle   //
+////////////////////////////////
+
+/// A signature produced by a signer that trusts a caller-supplied public key.
+///
+/// `signature` is the honest signature of `message` under `pub_key`, and
+/// `oracle_signature` what the flawed signer outputs when handed
+/// `supplied_pub_key` instead. A correct signer must refuse the latter
+/// request: both signatures share R, which reveals the secret scalar.
+pub struct KeySubstitutionVector {
+    pub comment: &'static str,
+    pub seed: [u8; 32],
+    pub pub_key: [u8; 32],
+    pub message: [u8; 32],
+    pub signature: Vec<u8>,
+    pub supplied_pub_key: [u8; 32],
+    pub oracle_signature: Vec<u8>,
+}
+
+impl Serialize for KeySubstitutionVector {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("KeySubstitutionVector", 7)?;
+        state.serialize_field("comment", &self.comment)?;
+        state.serialize_field("seed", &hex::encode(&self.seed))?;
+        state.serialize_field("pub_key", &hex::encode(&self.pub_key))?;
+        state.serialize_field("message", &hex::encode(&self.message))?;
+        state.serialize_field("signature", &hex::encode(&self.signature))?;
+        state.serialize_field("supplied_pub_key", &hex::encode(&self.supplied_pub_key))?;
+        state.serialize_field("oracle_signature", &hex::encode(&self.oracle_signature))?;
+        state.end()
+    }
+}
+
+/// Vectors for signers that accept a public key alongside the secret key:
+/// the supplied key is either unrelated to the secret, of small order, or the
+/// right key with a torsion component added (a mixed-order key, which hashes
+/// differently but has the same prime-order part).
+pub fn key_substitution_vectors() -> Vec<KeySubstitutionVector> {
+    let mut rng = new_rng();
+    let mut seed = [0u8; 32];
+    rng.fill_bytes(&mut seed);
+    let expanded = expand_seed(&seed);
+    let pub_key = expanded.public_key();
+    let pub_key_point = deserialize_point(&pub_key).unwrap();
+
+    let mut other_seed = [0u8; 32];
+    rng.fill_bytes(&mut other_seed);
+
+    let supplied_keys = [
+        ("unrelated public key", expand_seed(&other_seed).public_key()),
+        ("small order public key", EIGHT_TORSION[1]),
+        (
+            "public key plus a point of order 2",
+            (pub_key_point + deserialize_point(&EIGHT_TORSION[4]).unwrap())
+                .compress()
+                .to_bytes(),
+        ),
+        (
+            "public key plus a point of order 4",
+            (pub_key_point + deserialize_point(&EIGHT_TORSION[2]).unwrap())
+                .compress()
+                .to_bytes(),
+        ),
+        (
+            "public key plus a point of order 8",
+            (pub_key_point + pick_small_nonzero_point(rng.next_u64() as usize))
+                .compress()
+                .to_bytes(),
+        ),
+    ];
+
+    let mut vec = Vec::new();
+    for (comment, supplied_pub_key) in supplied_keys.iter() {
+        let mut message = [0u8; 32];
+        rng.fill_bytes(&mut message);
+        let tv = KeySubstitutionVector {
+            comment,
+            seed,
+            pub_key,
+            message,
+            signature: expanded.sign(&message),
+            supplied_pub_key: *supplied_pub_key,
+            oracle_signature: expanded.sign_with_public_key(supplied_pub_key, &message),
+        };
+        debug_assert!(expanded.sign_checked(supplied_pub_key, &message).is_err());
+        debug!(
+            "{}, signer must refuse\n\
+             \"pub_key\": \"{}\", \"supplied_pub_key\": \"{}\", \"message\": \"{}\", \"oracle_signature\": \"{}\"",
+            comment,
+            hex::encode(&tv.pub_key),
+            hex::encode(&tv.supplied_pub_key),
+            hex::encode(&tv.message),
+            hex::encode(&tv.oracle_signature)
+        );
+        vec.push(tv);
+    }
+    vec
+}
+
+/// Recover the secret scalar `a` (mod L) from two signatures of the same
+/// message sharing the same R, but hashed with two distinct public keys.
+///
+/// From `S1 = r + k1 * a` and `S2 = r + k2 * a`, `a = (S1 - S2) / (k1 - k2)`.
+pub fn recover_from_double_public_key(
+    message: &[u8],
+    pub_key1: &[u8],
+    signature1: &[u8],
+    pub_key2: &[u8],
+    signature2: &[u8],
+) -> Result<Scalar> {
+    check_slice_size(signature1, 64, "signature1")?;
+    check_slice_size(signature2, 64, "signature2")?;
+    if signature1[..32] != signature2[..32] {
+        return Err(anyhow!("signatures do not share the same R"));
+    }
+    let (r, s1) = deserialize_signature(signature1)?;
+    let (_, s2) = deserialize_signature(signature2)?;
+    let k1 = compute_hram_with_pk_array(message, check_slice_size(pub_key1, 32, "pub_key1")?, &r);
+    let k2 = compute_hram_with_pk_array(message, check_slice_size(pub_key2, 32, "pub_key2")?, &r);
+    if k1 == k2 {
+        return Err(anyhow!("both public keys yield the same hash"));
+    }
+    Ok((s1.reduce() - s2.reduce()) * (k1 - k2).invert())
+}
+
+/// Nonce derivations seen in, or plausible for, non-conforming signers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NonceDerivation {
+    /// `SHA-512(prefix || M)`, as specified
+    Rfc8032,
+    /// `SHA-512(M || prefix)`
+    MessageThenPrefix,
+    /// `SHA-512(seed || M)`, skipping the key expansion
+    SeedThenMessage,
+    /// `SHA-512(a || M)`, hashing the clamped secret scalar instead of the prefix
+    ScalarThenMessage,
+    /// `SHA-512(0^32 || M)`, i.e. a missing or zeroed prefix
+    ZeroPrefix,
+    /// `SHA-512(M)`
+    MessageOnly,
+}
+
+impl NonceDerivation {
+    const ALL: [NonceDerivation; 6] = [
+        NonceDerivation::Rfc8032,
+        NonceDerivation::MessageThenPrefix,
+        NonceDerivation::SeedThenMessage,
+        NonceDerivation::ScalarThenMessage,
+        NonceDerivation::ZeroPrefix,
+        NonceDerivation::MessageOnly,
+    ];
+
+    /// The nonce this derivation yields for `seed` and `message`
+    pub fn nonce(self, seed: &[u8; 32], message: &[u8]) -> Scalar {
+        let expanded = expand_seed(seed);
+        match self {
+            NonceDerivation::Rfc8032 => expanded.nonce(message),
+            NonceDerivation::MessageThenPrefix => hash_to_scalar(&[message, &expanded.prefix]),
+            NonceDerivation::SeedThenMessage => hash_to_scalar(&[seed, message]),
+            NonceDerivation::ScalarThenMessage => {
+                hash_to_scalar(&[&clamp(&expanded.raw_scalar), message])
+            }
+            NonceDerivation::ZeroPrefix => hash_to_scalar(&[&[0u8; 32], message]),
+            NonceDerivation::MessageOnly => hash_to_scalar(&[message]),
+        }
+    }
+}
+
+/// How a library's signature compares to the RFC 8032 reference
+#[derive(Debug, PartialEq, Eq)]
+pub enum SigningCheck {
+    /// Byte-identical to the reference signature
+    Identical,
+    /// Same R, and S equal to the reference S mod L, but not reduced
+    NonCanonicalS,
+    /// A valid signature with another nonce, recovered as `r = S - k * a`,
+    /// along with the derivation it matches, if any (none for e.g. a
+    /// randomized nonce)
+    NonceDeviation(Option<NonceDerivation>),
+    /// Not a valid signature of the message under the seed's public key
+    Invalid(String),
+}
+
+impl fmt::Display for SigningCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SigningCheck::Identical => write!(f, "identical to the RFC 8032 signature"),
+            SigningCheck::NonCanonicalS => write!(
+                f,
+                "same nonce as RFC 8032, but S is not reduced mod L"
+            ),
+            SigningCheck::NonceDeviation(Some(derivation)) => write!(
+                f,
+                "valid signature, but the nonce is derived as {:?} instead of SHA-512(prefix || M)",
+                derivation
+            ),
+            SigningCheck::NonceDeviation(None) => write!(
+                f,
+                "valid signature, but the nonce matches no known derivation (randomized?)"
+            ),
+            SigningCheck::Invalid(reason) => write!(f, "invalid signature: {}", reason),
+        }
+    }
+}
+
+/// Compare a library's signature of `message` under `seed` with the RFC 8032
+/// reference, and explain any difference in the nonce derivation.
+pub fn check_signature(seed: &[u8], message: &[u8], signature: &[u8]) -> Result<SigningCheck> {
+    let seed = decode_seed(seed)?;
+    check_slice_size(signature, 64, "signature")?;
+    let expanded = expand_seed(&seed);
+    let expected = expanded.sign(message);
+    if signature == &expected[..] {
+        return Ok(SigningCheck::Identical);
+    }
+
+    let pub_key = deserialize_point(&expanded.public_key())?;
+    let unpacked_signature = match deserialize_signature(signature) {
+        Ok(unpacked_signature) => unpacked_signature,
+        Err(e) => return Ok(SigningCheck::Invalid(e.to_string())),
+    };
+    if let Err(e) = verify_cofactorless(message, &pub_key, &unpacked_signature) {
+        return Ok(SigningCheck::Invalid(e.to_string()));
+    }
+
+    if signature[..32] == expected[..32] {
+        return Ok(SigningCheck::NonCanonicalS);
+    }
+
+    let s = unpacked_signature.1.reduce();
+    let k = compute_hram(message, &pub_key, &unpacked_signature.0);
+    let r = s - k * expanded.scalar();
+    if r * ED25519_BASEPOINT_POINT != unpacked_signature.0 {
+        return Err(anyhow!("recovered nonce does not match R"));
+    }
+    Ok(SigningCheck::NonceDeviation(
+        NonceDerivation::ALL
+            .iter()
+            .copied()
+            .find(|derivation| derivation.nonce(&seed, message) == r),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::non_reducing_scalar52::{self, Scalar52};
+    use std::convert::TryInto;
+
+    #[test]
+    fn test_rfc8032_vectors() {
+        for tv in signing_vectors().iter() {
+            let expanded = expand_seed(&tv.seed);
+            assert_eq!(expanded.public_key(), tv.pub_key, "{}", tv.comment);
+            assert_eq!(expanded.sign(&tv.message), tv.signature, "{}", tv.comment);
+            assert_eq!(
+                check_signature(&tv.seed, &tv.message, &tv.signature).unwrap(),
+                SigningCheck::Identical
+            );
+        }
+    }
+
+    #[test]
+    fn test_double_public_key_recovery() {
+        for tv in key_substitution_vectors().iter() {
+            let expanded = expand_seed(&tv.seed);
+            let a = recover_from_double_public_key(
+                &tv.message,
+                &tv.pub_key,
+                &tv.signature,
+                &tv.supplied_pub_key,
+                &tv.oracle_signature,
+            )
+            .unwrap();
+            assert_eq!(a, expanded.scalar().reduce(), "{}", tv.comment);
+
+            // the recovered scalar signs anything under the victim's key
+            let forged = expanded.sign_with_scalar(&a, b"forged");
+            let pub_key = deserialize_point(&tv.pub_key).unwrap();
+            assert!(
+                verify_cofactorless(b"forged", &pub_key, &deserialize_signature(&forged).unwrap())
+                    .is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn test_nonce_deviations() {
+        // TEST 3, as derivations coincide on an empty message
+        let tv = &signing_vectors()[2];
+        let expanded = expand_seed(&tv.seed);
+        let a = expanded.scalar();
+        let pub_key = a * ED25519_BASEPOINT_POINT;
+
+        for derivation in NonceDerivation::ALL.iter().skip(1) {
+            let r = derivation.nonce(&tv.seed, &tv.message);
+            let big_r = r * ED25519_BASEPOINT_POINT;
+            let s = r + compute_hram(&tv.message, &pub_key, &big_r) * a;
+            let signature = serialize_signature(&big_r, &s);
+            assert_eq!(
+                check_signature(&tv.seed, &tv.message, &signature).unwrap(),
+                SigningCheck::NonceDeviation(Some(*derivation))
+            );
+        }
+
+        let s = Scalar52::from_bytes(&expanded.sign(&tv.message)[32..].try_into().unwrap());
+        let mut signature = tv.signature.clone();
+        signature[32..].copy_from_slice(&Scalar52::add(&s, &non_reducing_scalar52::L).to_bytes());
+        assert_eq!(
+            check_signature(&tv.seed, &tv.message, &signature).unwrap(),
+            SigningCheck::NonCanonicalS
+        );
+
+        let mut signature = tv.signature.clone();
+        signature[63] ^= 1;
+        assert!(matches!(
+            check_signature(&tv.seed, &tv.message, &signature).unwrap(),
+            SigningCheck::Invalid(_)
+        ));
+    }
+}
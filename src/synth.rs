@@ -0,0 +1,417 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Synthesis of test vectors from declared properties.
+//!
+//! A `Spec` gives the order and encoding of A and R, the class of S, and the
+//! outcome required under each reference policy. As in `grid`,
+//! `A = [a]B + T_A` and `R = [r]B + T_R`, with torsion components of the
+//! requested orders. For each message, S is tried as `r + k * a` for the
+//! re-encoded hash, for the raw hash, and as a random scalar, each put in the
+//! requested class. We grind keys, nonces and messages until the outcomes
+//! match, and report the spec as infeasible otherwise.
+
+use anyhow::{anyhow, Result};
+use core::fmt;
+use core::str::FromStr;
+use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT, scalar::Scalar};
+use rand::{seq::SliceRandom, RngCore};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::grid::{random_nonzero_scalar, ScalarClass};
+use crate::inspect::inspect_point;
+use crate::non_reducing_scalar52::Scalar52;
+use crate::policy::REFERENCE_POLICIES;
+use crate::{
+    compute_hram, compute_hram_with_arrays, deserialize_point, new_rng, TestVector, EIGHT_TORSION,
+    EIGHT_TORSION_NON_CANONICAL,
+};
+
+/// How many keys, nonces and messages `synthesize` tries by default
+pub const MAX_ATTEMPTS: usize = 1024;
+
+/// A point `[x]B + T`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PointSpec {
+    /// x is non-zero
+    pub prime_component: bool,
+    /// The order of T: 1, 2, 4 or 8
+    pub torsion_order: u8,
+    pub canonical: bool,
+}
+
+/// Parses the orders of `PointReport::order`, `1` to `8L`, with a `:nc`
+/// suffix for a non-canonical encoding
+impl FromStr for PointSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<PointSpec> {
+        let (order, canonical) = match s.strip_suffix(":nc") {
+            Some(order) => (order, false),
+            None => (s, true),
+        };
+        let (torsion_order, prime_component) = match order.strip_suffix('L') {
+            Some("") => ("1", true),
+            Some(torsion_order) => (torsion_order, true),
+            None => (order, false),
+        };
+        match torsion_order.parse() {
+            Ok(torsion_order) if [1, 2, 4, 8].contains(&torsion_order) => Ok(PointSpec {
+                prime_component,
+                torsion_order,
+                canonical,
+            }),
+            _ => Err(anyhow!(
+                "invalid point {}, expected an order among 1, 2, 4, 8, L, 2L, 4L, 8L, \
+                 optionally followed by :nc",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for PointSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.torsion_order, self.prime_component) {
+            (order, false) => write!(f, "of order {}", order)?,
+            (1, true) => write!(f, "of order L")?,
+            (order, true) => write!(f, "of order {}L", order)?,
+        }
+        if !self.canonical {
+            write!(f, ", non-canonical")?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Spec {
+    pub a: PointSpec,
+    pub r: PointSpec,
+    pub s_class: ScalarClass,
+    /// `V` (accepted), `X` (rejected) or `?` (either) under each of
+    /// `REFERENCE_POLICIES`
+    pub outcomes: String,
+}
+
+impl Spec {
+    /// Parse the command line arguments of `synthesize`
+    pub fn parse(a: &str, r: &str, s_class: &str, outcomes: &str) -> Result<Spec> {
+        let s_class = match s_class {
+            "zero" => ScalarClass::Zero,
+            "reduced" => ScalarClass::Reduced,
+            "unreduced" => ScalarClass::Unreduced,
+            other => {
+                return Err(anyhow!(
+                    "invalid S class {}, expected zero, reduced or unreduced",
+                    other
+                ))
+            }
+        };
+        if outcomes.chars().count() != REFERENCE_POLICIES.len()
+            || outcomes.chars().any(|c| !"VX?".contains(c))
+        {
+            return Err(anyhow!(
+                "invalid outcomes {}, expected one of V, X or ? for each of {}",
+                outcomes,
+                REFERENCE_POLICIES
+                    .iter()
+                    .map(|p| p.name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        Ok(Spec {
+            a: a.parse()?,
+            r: r.parse()?,
+            s_class,
+            outcomes: outcomes.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for Spec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "A {}, R {}, {}, outcomes {}",
+            self.a, self.r, self.s_class, self.outcomes
+        )
+    }
+}
+
+pub struct SynthesizedVector {
+    pub comment: String,
+    pub vector: TestVector,
+    pub accepted_by: Vec<&'static str>,
+    /// How many keys, nonces and messages were tried
+    pub attempts: usize,
+}
+
+impl Serialize for SynthesizedVector {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("SynthesizedVector", 6)?;
+        state.serialize_field("comment", &self.comment)?;
+        state.serialize_field("message", &hex::encode(&self.vector.message))?;
+        state.serialize_field("pub_key", &hex::encode(&self.vector.pub_key))?;
+        state.serialize_field("signature", &hex::encode(&self.vector.signature))?;
+        state.serialize_field("accepted_by", &self.accepted_by)?;
+        state.serialize_field("attempts", &self.attempts)?;
+        state.end()
+    }
+}
+
+/// The encodings of the torsion points of `spec`
+fn torsion_encodings(spec: &PointSpec) -> Vec<[u8; 32]> {
+    let encodings: &[[u8; 32]] = if spec.canonical {
+        &EIGHT_TORSION
+    } else {
+        &EIGHT_TORSION_NON_CANONICAL
+    };
+    encodings
+        .iter()
+        .filter(|encoding| {
+            let report = inspect_point(&encoding[..]).expect("a point encoding is 32 bytes");
+            report.decoded.unwrap().torsion_order == spec.torsion_order
+        })
+        .copied()
+        .collect()
+}
+
+/// The reasons no point can satisfy `spec`, if any
+fn check_point(name: &str, spec: &PointSpec) -> Result<()> {
+    if spec.canonical {
+        return Ok(());
+    }
+    // the only non-canonical encodings of points with a prime-order component
+    // have y < 19, and we do not know their discrete log to sign with
+    if spec.prime_component {
+        return Err(anyhow!(
+            "{} {} is infeasible: we only have non-canonical encodings of small points",
+            name,
+            spec
+        ));
+    }
+    if torsion_encodings(spec).is_empty() {
+        return Err(anyhow!(
+            "{} {} is infeasible: no point of order {} has a non-canonical encoding",
+            name,
+            spec,
+            spec.torsion_order
+        ));
+    }
+    Ok(())
+}
+
+/// A random encoding of a point of `spec`, with its prime-order discrete log
+fn point(rng: &mut impl RngCore, spec: &PointSpec) -> ([u8; 32], Scalar) {
+    let torsion = *torsion_encodings(spec).choose(rng).unwrap();
+    if !spec.prime_component {
+        return (torsion, Scalar::zero());
+    }
+    let scalar = random_nonzero_scalar(rng);
+    let point = scalar * ED25519_BASEPOINT_POINT + deserialize_point(&torsion).unwrap();
+    (point.compress().to_bytes(), scalar)
+}
+
+/// `s` put in `class`, if possible
+fn in_class(s: Scalar, class: ScalarClass) -> Option<[u8; 32]> {
+    match class {
+        ScalarClass::Zero => Some([0u8; 32]),
+        ScalarClass::Reduced if s == Scalar::zero() => None,
+        ScalarClass::Reduced => Some(s.to_bytes()),
+        ScalarClass::Unreduced => Some(
+            Scalar52::add_multiple_of_l(&Scalar52::from_bytes(&s.to_bytes()), 1)
+                .unwrap()
+                .to_bytes(),
+        ),
+    }
+}
+
+/// The index of the first policy whose outcome for `tv` is not the
+/// `required` one, if any. Stops there, as verification is what grinding
+/// spends its time on.
+fn first_mismatch(tv: &TestVector, required: &str) -> Option<usize> {
+    REFERENCE_POLICIES
+        .iter()
+        .zip(required.chars())
+        .position(|(p, required)| {
+            let accepted = p.verify(&tv.message, &tv.pub_key, &tv.signature).is_ok();
+            (required == 'V' && !accepted) || (required == 'X' && accepted)
+        })
+}
+
+/// Grind a vector satisfying `spec`, trying up to `max_attempts` keys,
+/// nonces and messages
+pub fn synthesize(spec: &Spec, max_attempts: usize) -> Result<SynthesizedVector> {
+    check_point("A", &spec.a)?;
+    check_point("R", &spec.r)?;
+
+    let mut rng = new_rng();
+    // the furthest policy a candidate got to before its outcome mismatched
+    let mut furthest = 0;
+    for attempt in 1..=max_attempts {
+        let (pub_key, a) = point(&mut rng, &spec.a);
+        let (r_bytes, r) = point(&mut rng, &spec.r);
+        let mut message = [0u8; 32];
+        rng.fill_bytes(&mut message);
+
+        let reencoded_hash = compute_hram(
+            &message,
+            &deserialize_point(&pub_key).unwrap(),
+            &deserialize_point(&r_bytes).unwrap(),
+        );
+        let raw_hash = compute_hram_with_arrays(&message, &pub_key, &r_bytes);
+        let mut candidates: Vec<[u8; 32]> = [
+            r + reencoded_hash * a,
+            r + raw_hash * a,
+            random_nonzero_scalar(&mut rng),
+        ]
+        .iter()
+        .filter_map(|s| in_class(*s, spec.s_class))
+        .collect();
+        // S = 0 whatever the candidate, and equal hashes for canonical encodings
+        candidates.dedup();
+        for s_bytes in candidates.iter() {
+            let mut signature = r_bytes.to_vec();
+            signature.extend_from_slice(s_bytes);
+            let tv = TestVector {
+                message: message.to_vec(),
+                pub_key,
+                signature,
+            };
+
+            match first_mismatch(&tv, &spec.outcomes) {
+                None => {
+                    debug!("{} after {} attempts", spec, attempt);
+                    let accepted_by = REFERENCE_POLICIES
+                        .iter()
+                        .filter(|p| p.verify(&tv.message, &tv.pub_key, &tv.signature).is_ok())
+                        .map(|p| p.name)
+                        .collect();
+                    return Ok(SynthesizedVector {
+                        comment: spec.to_string(),
+                        vector: tv,
+                        accepted_by,
+                        attempts: attempt,
+                    });
+                }
+                Some(idx) => furthest = furthest.max(idx),
+            }
+        }
+    }
+    let policy = &REFERENCE_POLICIES[furthest];
+    Err(anyhow!(
+        "{} is infeasible: no vector found in {} attempts, none {} by {} (with the \
+         policies before it as required)",
+        spec,
+        max_attempts,
+        if spec.outcomes.chars().nth(furthest) == Some('V') {
+            "accepted"
+        } else {
+            "rejected"
+        },
+        policy.name
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_test_vectors;
+    use std::convert::TryInto;
+
+    /// `V` or `X` under each of `REFERENCE_POLICIES`
+    fn outcomes(tv: &TestVector) -> String {
+        REFERENCE_POLICIES
+            .iter()
+            .map(
+                |p| match p.verify(&tv.message, &tv.pub_key, &tv.signature) {
+                    Ok(()) => 'V',
+                    Err(_) => 'X',
+                },
+            )
+            .collect()
+    }
+
+    fn point_spec(bytes: &[u8]) -> PointSpec {
+        let report = inspect_point(bytes).unwrap();
+        let decoded = report.decoded.as_ref().unwrap();
+        PointSpec {
+            prime_component: decoded.has_prime_component,
+            torsion_order: decoded.torsion_order,
+            canonical: report.is_canonical(),
+        }
+    }
+
+    #[test]
+    fn test_synthesize_generated_vectors() {
+        // every hand-written case can be synthesized from its properties
+        for tv in generate_test_vectors().iter() {
+            let spec = Spec {
+                a: point_spec(&tv.pub_key),
+                r: point_spec(&tv.signature[..32]),
+                s_class: ScalarClass::of(&tv.signature[32..].try_into().unwrap()),
+                outcomes: outcomes(tv),
+            };
+            let synthesized = synthesize(&spec, MAX_ATTEMPTS).unwrap();
+            let vector = &synthesized.vector;
+            assert_eq!(outcomes(vector), spec.outcomes, "{}", spec);
+            assert_eq!(point_spec(&vector.pub_key), spec.a, "{}", spec);
+            assert_eq!(point_spec(&vector.signature[..32]), spec.r, "{}", spec);
+            assert_eq!(
+                ScalarClass::of(&vector.signature[32..].try_into().unwrap()),
+                spec.s_class
+            );
+        }
+    }
+
+    #[test]
+    fn test_spec_parsing() {
+        let spec = Spec::parse("8L", "4:nc", "unreduced", "V???????").unwrap();
+        assert_eq!(
+            spec.to_string(),
+            "A of order 8L, R of order 4, non-canonical, S ≥ L, outcomes V???????"
+        );
+        assert_eq!("L".parse::<PointSpec>().unwrap().torsion_order, 1);
+        assert!("3L".parse::<PointSpec>().is_err());
+        assert!("8:canonical".parse::<PointSpec>().is_err());
+        assert!(Spec::parse("8", "8", "large", "????????").is_err());
+        assert!(Spec::parse("8", "8", "zero", "VVV").is_err());
+        assert!(Spec::parse("8", "8", "zero", "VVVVVVVY").is_err());
+    }
+
+    #[test]
+    fn test_infeasible_specs() {
+        let err = synthesize(
+            &Spec::parse("L:nc", "L", "reduced", "????????").unwrap(),
+            MAX_ATTEMPTS,
+        )
+        .err()
+        .unwrap();
+        assert!(err
+            .to_string()
+            .contains("non-canonical encodings of small points"));
+        let err = synthesize(
+            &Spec::parse("8", "8:nc", "reduced", "????????").unwrap(),
+            MAX_ATTEMPTS,
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("no point of order 8"));
+        // S = 0 cannot satisfy the equation with a prime-order R and small A
+        let err = synthesize(&Spec::parse("8", "L", "zero", "V???????").unwrap(), 64)
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string()
+                .contains("no vector found in 64 attempts, none accepted by cofactored"),
+            "{}",
+            err
+        );
+    }
+}
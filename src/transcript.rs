@@ -0,0 +1,236 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Verification transcripts: every intermediate value of a reference policy's
+//! verification, in JSON, to diff against instrumented library builds.
+//!
+//! Points are given as their canonical encodings and scalars as 32 bytes
+//! little-endian, both in hex. For pre-reduced cofactored verification,
+//! `s_b`, `k_a` and `r_prime` use `8S mod L` and `8k mod L`, and the residual
+//! is `[8]R - R'`.
+
+use anyhow::Result;
+use core::ops::Neg;
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_POINT, edwards::EdwardsPoint, scalar::Scalar, traits::IsIdentity,
+};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::inspect::{decompose, inspect_point};
+use crate::policy::{Equation, Policy};
+use crate::{check_slice_size, compute_hram, compute_hram_with_arrays, deserialize_point};
+
+/// The values depending on R and A decoding
+pub struct Intermediates {
+    pub r: EdwardsPoint,
+    pub a: EdwardsPoint,
+    pub reencoded_hash: Scalar,
+    /// The hash the policy uses, raw or re-encoded
+    pub k: Scalar,
+    pub s_b: EdwardsPoint,
+    pub k_a: EdwardsPoint,
+    /// `[S]B - [k]A`
+    pub r_prime: EdwardsPoint,
+    /// `R - R'`
+    pub residual: EdwardsPoint,
+    /// The equation holds: the residual, times 8 if cofactored, is the identity
+    pub equation_holds: bool,
+}
+
+pub struct Transcript {
+    pub policy: &'static str,
+    pub equation: Equation,
+    pub raw_hash: Scalar,
+    /// `None` if R or A does not decode
+    pub intermediates: Option<Intermediates>,
+    /// The outcome of `Policy::verify`, which also checks encodings, orders
+    /// and the range of S
+    pub result: Result<(), String>,
+}
+
+/// Verify `signature` of `message` under `pub_key` per `policy`, recording
+/// every intermediate value
+pub fn trace(
+    policy: &Policy,
+    message: &[u8],
+    pub_key: &[u8],
+    signature: &[u8],
+) -> Result<Transcript> {
+    let pub_key_bytes = check_slice_size(pub_key, 32, "pub_key")?;
+    let signature = check_slice_size(signature, 64, "signature")?;
+    let (r_bytes, s_bytes) = signature.split_at(32);
+    let mut s_arr = [0u8; 32];
+    s_arr.copy_from_slice(s_bytes);
+    let s = Scalar::from_bytes_mod_order(s_arr);
+
+    let raw_hash = compute_hram_with_arrays(message, pub_key_bytes, r_bytes);
+    let intermediates = match (deserialize_point(pub_key_bytes), deserialize_point(r_bytes)) {
+        (Ok(a), Ok(r)) => {
+            let reencoded_hash = compute_hram(message, &a, &r);
+            let k = if policy.reserialize {
+                reencoded_hash
+            } else {
+                raw_hash
+            };
+            let (k_eq, s_eq, r_eq) = match policy.equation {
+                Equation::PreReducedCofactored => {
+                    let eight = Scalar::from(8u64);
+                    (eight * k, eight * s, r.mul_by_cofactor())
+                }
+                _ => (k, s, r),
+            };
+            let s_b = s_eq * ED25519_BASEPOINT_POINT;
+            let k_a = k_eq * a;
+            let r_prime = EdwardsPoint::vartime_double_scalar_mul_basepoint(&k_eq, &a.neg(), &s_eq);
+            let residual = r_eq - r_prime;
+            let equation_holds = match policy.equation {
+                Equation::Cofactored => residual.mul_by_cofactor().is_identity(),
+                _ => residual.is_identity(),
+            };
+            Some(Intermediates {
+                r,
+                a,
+                reencoded_hash,
+                k,
+                s_b,
+                k_a,
+                r_prime,
+                residual,
+                equation_holds,
+            })
+        }
+        _ => None,
+    };
+
+    Ok(Transcript {
+        policy: policy.name,
+        equation: policy.equation,
+        raw_hash,
+        intermediates,
+        result: policy
+            .verify(message, pub_key_bytes, signature)
+            .map_err(|e| e.to_string()),
+    })
+}
+
+fn point_hex(point: &EdwardsPoint) -> String {
+    hex::encode(point.compress().as_bytes())
+}
+
+impl Serialize for Transcript {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Transcript", 16)?;
+        state.serialize_field("policy", self.policy)?;
+        state.serialize_field(
+            "check",
+            match self.equation {
+                Equation::Cofactored => "[8](R - R') = 0",
+                Equation::Cofactorless => "R - R' = 0",
+                Equation::PreReducedCofactored => "[8]R - R' = 0",
+            },
+        )?;
+        state.serialize_field("k_raw", &hex::encode(self.raw_hash.as_bytes()))?;
+        match &self.intermediates {
+            Some(i) => {
+                let residual = inspect_point(i.residual.compress().as_bytes())
+                    .expect("a point encoding is 32 bytes");
+                state.serialize_field("r", &point_hex(&i.r))?;
+                state.serialize_field("a", &point_hex(&i.a))?;
+                state.serialize_field("k_reencoded", &hex::encode(i.reencoded_hash.as_bytes()))?;
+                state.serialize_field("k", &hex::encode(i.k.as_bytes()))?;
+                state.serialize_field("s_b", &point_hex(&i.s_b))?;
+                state.serialize_field("k_a", &point_hex(&i.k_a))?;
+                state.serialize_field("r_prime", &point_hex(&i.r_prime))?;
+                state.serialize_field("residual", &point_hex(&i.residual))?;
+                state.serialize_field("residual_order", &residual.order())?;
+                state.serialize_field("residual_torsion_index", &decompose(&i.residual).1)?;
+                state.serialize_field("equation_holds", &i.equation_holds)?;
+            }
+            None => {
+                for field in [
+                    "r",
+                    "a",
+                    "k_reencoded",
+                    "k",
+                    "s_b",
+                    "k_a",
+                    "r_prime",
+                    "residual",
+                    "residual_order",
+                    "residual_torsion_index",
+                    "equation_holds",
+                ]
+                .iter()
+                {
+                    state.serialize_field(field, &None::<()>)?;
+                }
+            }
+        }
+        state.serialize_field("accepted", &self.result.is_ok())?;
+        state.serialize_field("reason", &self.result.as_ref().err())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_test_vectors;
+    use crate::policy::{COFACTORED, COFACTORLESS, REFERENCE_POLICIES};
+
+    #[test]
+    fn test_transcripts_match_verification() {
+        for tv in generate_test_vectors().iter() {
+            for policy in REFERENCE_POLICIES.iter() {
+                let transcript = trace(policy, &tv.message, &tv.pub_key, &tv.signature).unwrap();
+                let i = transcript.intermediates.as_ref().unwrap();
+                assert_eq!(i.r_prime, i.s_b - i.k_a);
+                // the equation is the last check
+                if transcript.result.is_ok() {
+                    assert!(i.equation_holds, "{}", policy.name);
+                }
+            }
+
+            // cofactored and cofactorless verification differ by the
+            // torsion component of the residual
+            let cofactored = trace(&COFACTORED, &tv.message, &tv.pub_key, &tv.signature).unwrap();
+            let cofactorless =
+                trace(&COFACTORLESS, &tv.message, &tv.pub_key, &tv.signature).unwrap();
+            let residual = cofactored.intermediates.unwrap().residual;
+            assert_eq!(cofactorless.intermediates.unwrap().residual, residual);
+            assert_eq!(
+                cofactored.result.is_ok(),
+                decompose(&residual).0.is_identity()
+            );
+            assert_eq!(cofactorless.result.is_ok(), residual.is_identity());
+        }
+    }
+
+    #[test]
+    fn test_transcript_json() {
+        let tv = &generate_test_vectors()[4];
+        let json = serde_json::to_value(
+            &trace(&COFACTORLESS, &tv.message, &tv.pub_key, &tv.signature).unwrap(),
+        )
+        .unwrap();
+        // #4 passes cofactored, fails cofactorless
+        assert_eq!(json["accepted"], false);
+        assert_eq!(json["reason"], "Invalid cofactorless signature");
+        assert_ne!(json["residual_torsion_index"], 0);
+        assert!(!json["residual_order"].as_str().unwrap().contains('L'));
+
+        let mut pub_key = [0u8; 32];
+        pub_key[0] = 2;
+        let json = serde_json::to_value(
+            &trace(&COFACTORED, &tv.message, &pub_key, &tv.signature).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(json["reason"], "A does not decode");
+        assert!(json["residual"].is_null());
+    }
+}
@@ -0,0 +1,704 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the APACHE 2.0 license found in
+// the LICENSE file in the root directory of this source tree.
+
+//! Validation of the generated test vectors against their declared
+//! properties, in every build.
+//!
+//! The generators check their output with `debug_assert!`, which release
+//! builds skip. The functions here run in all builds, one per file of
+//! `generate`: `validate` classifies each vector of `cases.json` and runs
+//! every reference policy over it, the others recompute the classes of the
+//! grid, the policies the ownership and non-repudiation vectors are meant to
+//! fool, and the key generation, signing, key substitution and malleability
+//! vectors from their seed or honest signature. `test_vector_files` fails,
+//! before any file is written, if a vector does not match its declaration.
+
+use anyhow::{anyhow, Result};
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_POINT, edwards::EdwardsPoint, scalar::Scalar, traits::IsIdentity,
+};
+use sha2::{Digest, Sha512};
+use std::convert::TryInto;
+
+use crate::grid::{
+    GridVector,
+    PointClass::{self, Mixed, Prime, Small},
+    ScalarClass::{self, Reduced, Unreduced, Zero},
+};
+use crate::malleability::{MalleabilityVector, Producer, Variant};
+use crate::ownership::OwnershipVector;
+use crate::policy::{self, Equation, Policy, REFERENCE_POLICIES};
+use crate::repudiation::RepudiationVector;
+use crate::signing::{self, KeySubstitutionVector, KeygenVector, SigningVector};
+use crate::{deserialize_point, TestVector};
+
+/// What a vector of `generate_test_vectors` is meant to be
+pub struct Declared {
+    /// The function generating the vector
+    pub generator: &'static str,
+    pub s_class: ScalarClass,
+    pub a_class: PointClass,
+    pub r_class: PointClass,
+    pub a_canonical: bool,
+    pub r_canonical: bool,
+    /// `V` (accepted) or `X` (rejected) under each of `REFERENCE_POLICIES`
+    pub accepted: &'static str,
+}
+
+const fn declared(
+    generator: &'static str,
+    s_class: ScalarClass,
+    (a_class, a_canonical): (PointClass, bool),
+    (r_class, r_canonical): (PointClass, bool),
+    accepted: &'static str,
+) -> Declared {
+    Declared {
+        generator,
+        s_class,
+        a_class,
+        r_class,
+        a_canonical,
+        r_canonical,
+        accepted,
+    }
+}
+
+// The policies are, in order: cofactored, cofactorless, pre-reduced
+// cofactored, cofactored and cofactorless over the raw bytes, RFC 8032,
+// ZIP-215 and strict
+pub const DECLARED: [Declared; 12] = [
+    declared(
+        "zero_small_small",
+        Zero,
+        (Small, true),
+        (Small, true),
+        "VVXVVVVX",
+    ),
+    declared(
+        "non_zero_mixed_small",
+        Reduced,
+        (Small, true),
+        (Mixed, true),
+        "VVXVVVVX",
+    ),
+    declared(
+        "non_zero_small_mixed",
+        Reduced,
+        (Mixed, true),
+        (Small, true),
+        "VVXVVVVX",
+    ),
+    declared(
+        "non_zero_mixed_mixed",
+        Reduced,
+        (Mixed, true),
+        (Mixed, true),
+        "VVXVVVVV",
+    ),
+    declared(
+        "non_zero_mixed_mixed",
+        Reduced,
+        (Mixed, true),
+        (Mixed, true),
+        "VXXVXXVX",
+    ),
+    declared(
+        "pre_reduced_scalar",
+        Reduced,
+        (Mixed, true),
+        (Prime, true),
+        "VXXVXXVX",
+    ),
+    declared(
+        "large_s",
+        Unreduced,
+        (Prime, true),
+        (Prime, true),
+        "VVVVVXXX",
+    ),
+    declared(
+        "really_large_s",
+        Unreduced,
+        (Prime, true),
+        (Prime, true),
+        "VVVVVXXX",
+    ),
+    declared(
+        "non_zero_small_non_canonical_mixed",
+        Reduced,
+        (Mixed, true),
+        (Small, false),
+        "VVXXXXXX",
+    ),
+    declared(
+        "non_zero_small_non_canonical_mixed",
+        Reduced,
+        (Mixed, true),
+        (Small, false),
+        "XXXVVXVX",
+    ),
+    declared(
+        "non_zero_mixed_small_non_canonical",
+        Reduced,
+        (Small, false),
+        (Mixed, true),
+        "VVVVXXVX",
+    ),
+    declared(
+        "non_zero_mixed_small_non_canonical",
+        Reduced,
+        (Small, false),
+        (Mixed, true),
+        "VXVVVXVX",
+    ),
+];
+
+/// Every way the point encoded as `bytes` differs from its declaration
+fn check_point(name: &str, bytes: &[u8], class: PointClass, canonical: bool) -> Vec<String> {
+    let point = match deserialize_point(bytes) {
+        Ok(point) => point,
+        Err(_) => return vec![format!("{} does not decode", name)],
+    };
+    let mut mismatches = Vec::new();
+    if PointClass::of(&point) != class {
+        mismatches.push(format!(
+            "{} is {}, declared {}",
+            name,
+            PointClass::of(&point),
+            class
+        ));
+    }
+    if (point.compress().as_bytes() == bytes) != canonical {
+        mismatches.push(format!(
+            "{} is {}canonically encoded",
+            name,
+            if canonical { "not " } else { "" }
+        ));
+    }
+    mismatches
+}
+
+/// Every way `tv` differs from `declared`
+fn check(tv: &TestVector, declared: &Declared) -> Vec<String> {
+    let mut mismatches = check_point("A", &tv.pub_key, declared.a_class, declared.a_canonical);
+    if tv.signature.len() != 64 {
+        mismatches.push(format!("the signature is {} bytes", tv.signature.len()));
+        return mismatches;
+    }
+    mismatches.extend(check_point(
+        "R",
+        &tv.signature[..32],
+        declared.r_class,
+        declared.r_canonical,
+    ));
+
+    let s_class = ScalarClass::of(&tv.signature[32..].try_into().unwrap());
+    if s_class != declared.s_class {
+        mismatches.push(format!("{}, declared {}", s_class, declared.s_class));
+    }
+    for (policy, expected) in REFERENCE_POLICIES.iter().zip(declared.accepted.chars()) {
+        match (
+            policy.verify(&tv.message, &tv.pub_key, &tv.signature),
+            expected,
+        ) {
+            (Ok(()), 'X') => mismatches.push(format!("{} accepts it", policy.name)),
+            (Err(e), 'V') => mismatches.push(format!("{} rejects it: {}", policy.name, e)),
+            _ => (),
+        }
+    }
+    mismatches
+}
+
+/// Check each of `vectors` with `check`, and fail with every mismatch of
+/// `file`, labeled with the index and `label` of its vector
+fn check_all<T>(
+    file: &str,
+    vectors: &[T],
+    label: impl Fn(usize, &T) -> String,
+    check: impl Fn(usize, &T) -> Vec<String>,
+) -> Result<()> {
+    let mismatches: Vec<String> = vectors
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, tv)| {
+            let label = label(idx, tv);
+            check(idx, tv)
+                .into_iter()
+                .map(move |m| format!("vector #{} ({}): {}", idx, label, m))
+        })
+        .collect();
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "{}: generated vectors do not match their declaration\n  {}",
+            file,
+            mismatches.join("\n  ")
+        ))
+    }
+}
+
+/// Check the vectors of `generate_test_vectors` against `DECLARED`
+pub fn validate(vectors: &[TestVector]) -> Result<()> {
+    if vectors.len() != DECLARED.len() {
+        return Err(anyhow!(
+            "cases.json: {} vectors generated, {} declared",
+            vectors.len(),
+            DECLARED.len()
+        ));
+    }
+    check_all(
+        "cases.json",
+        vectors,
+        |idx, _| DECLARED[idx].generator.to_string(),
+        |idx, tv| check(tv, &DECLARED[idx]),
+    )
+}
+
+/// The RFC 8032 policy, which every honest signature satisfies
+fn rfc_8032() -> Policy {
+    Policy::by_name("RFC 8032").unwrap()
+}
+
+/// Why `policy` rejects `signature` of `message` under `pub_key`, if it does
+fn check_accepts(policy: &Policy, message: &[u8], pub_key: &[u8], signature: &[u8]) -> Vec<String> {
+    match policy.verify(message, pub_key, signature) {
+        Ok(()) => Vec::new(),
+        Err(e) => vec![format!("{} rejects it: {}", policy.name, e)],
+    }
+}
+
+/// Check that each grid vector has its declared classes of A, R and S, and
+/// that the grid covers every combination of them once
+pub fn validate_grid(vectors: &[GridVector]) -> Result<()> {
+    let cells = PointClass::ALL.len() * PointClass::ALL.len() * ScalarClass::ALL.len();
+    if vectors.len() != cells {
+        return Err(anyhow!(
+            "grid_cases.json: {} vectors generated, for {} cells",
+            vectors.len(),
+            cells
+        ));
+    }
+    check_all(
+        "grid_cases.json",
+        vectors,
+        |_, tv| tv.comment(),
+        |idx, tv| {
+            let mut mismatches = Vec::new();
+            if vectors[..idx].iter().any(|other| {
+                (other.a_class, other.r_class, other.s_class)
+                    == (tv.a_class, tv.r_class, tv.s_class)
+            }) {
+                mismatches.push("its cell is already covered".to_string());
+            }
+            mismatches.extend(check_point("A", &tv.pub_key, tv.a_class, true));
+            if tv.signature.len() != 64 {
+                mismatches.push(format!("the signature is {} bytes", tv.signature.len()));
+                return mismatches;
+            }
+            mismatches.extend(check_point("R", &tv.signature[..32], tv.r_class, true));
+            let s_class = ScalarClass::of(&tv.signature[32..].try_into().unwrap());
+            if s_class != tv.s_class {
+                mismatches.push(format!("{}, declared {}", s_class, tv.s_class));
+            }
+            mismatches
+        },
+    )
+}
+
+/// Check that each ownership vector verifies under both public keys per its
+/// policy, and, for cofactored vectors, under neither per cofactorless
+/// verification
+pub fn validate_ownership(vectors: &[OwnershipVector]) -> Result<()> {
+    check_all(
+        "ownership_cases.json",
+        vectors,
+        |_, tv| tv.comment.clone(),
+        |_, tv| {
+            let policy = match Policy::by_name(tv.policy) {
+                Some(policy) => policy,
+                None => return vec![format!("unknown policy {}", tv.policy)],
+            };
+            let mut mismatches = Vec::new();
+            if tv.pub_key1 == tv.pub_key2 {
+                mismatches.push("both public keys are the same".to_string());
+            }
+            for (name, pub_key) in [("pub_key1", &tv.pub_key1), ("pub_key2", &tv.pub_key2)].iter() {
+                mismatches.extend(
+                    check_accepts(&policy, &tv.message, *pub_key, &tv.signature)
+                        .into_iter()
+                        .map(|m| format!("{}: {}", name, m)),
+                );
+                if policy == policy::COFACTORED
+                    && policy::COFACTORLESS
+                        .verify(&tv.message, *pub_key, &tv.signature)
+                        .is_ok()
+                {
+                    mismatches.push(format!("{}: cofactorless accepts it", name));
+                }
+            }
+            mismatches
+        },
+    )
+}
+
+/// The order of `point`, if it is of small order
+fn small_order(point: &EdwardsPoint) -> Option<u8> {
+    let mut multiple = *point;
+    for order in [1, 2, 4, 8].iter() {
+        if multiple.is_identity() {
+            return Some(*order);
+        }
+        multiple = multiple + multiple;
+    }
+    None
+}
+
+/// Check that each non-repudiation vector has a public key of its declared
+/// order, verifies for both messages per its policy, and, for policies with a
+/// cofactored equation and a public key other than the identity, for neither
+/// per cofactorless verification
+pub fn validate_repudiation(vectors: &[RepudiationVector]) -> Result<()> {
+    check_all(
+        "repudiation_cases.json",
+        vectors,
+        |_, tv| format!("order {}, {}", tv.torsion_order, tv.policy),
+        |_, tv| {
+            let policy = match Policy::by_name(tv.policy) {
+                Some(policy) => policy,
+                None => return vec![format!("unknown policy {}", tv.policy)],
+            };
+            let mut mismatches = Vec::new();
+            match deserialize_point(&tv.pub_key).map(|a| small_order(&a)) {
+                Ok(Some(order)) if order == tv.torsion_order => (),
+                Ok(Some(order)) => mismatches.push(format!("A is of order {}", order)),
+                Ok(None) => mismatches.push("A is not of small order".to_string()),
+                Err(_) => mismatches.push("A does not decode".to_string()),
+            }
+            if tv.message1 == tv.message2 {
+                mismatches.push("both messages are the same".to_string());
+            }
+            let exclusive = policy.equation != Equation::Cofactorless && tv.torsion_order > 1;
+            for (name, message) in [("message1", tv.message1), ("message2", tv.message2)].iter() {
+                mismatches.extend(
+                    check_accepts(&policy, message, &tv.pub_key, &tv.signature)
+                        .into_iter()
+                        .map(|m| format!("{}: {}", name, m)),
+                );
+                if exclusive
+                    && policy::COFACTORLESS
+                        .verify(message, &tv.pub_key, &tv.signature)
+                        .is_ok()
+                {
+                    mismatches.push(format!("{}: cofactorless accepts it", name));
+                }
+            }
+            mismatches
+        },
+    )
+}
+
+/// Check that each malleability vector is derived from the honest signature,
+/// the first vector, as its variant says
+pub fn validate_malleability(vectors: &[MalleabilityVector]) -> Result<()> {
+    let honest = match vectors.first() {
+        Some(honest) if honest.variant == Variant::Honest => honest,
+        _ => {
+            return Err(anyhow!(
+                "malleability_cases.json: the first vector is not the honest signature"
+            ))
+        }
+    };
+    let r = honest
+        .signature
+        .get(..32)
+        .and_then(|r| deserialize_point(r).ok());
+    let s = honest.signature.get(32..).filter(|s| s.len() == 32);
+    let (r, s) = match (r, s) {
+        (Some(r), Some(s)) => (r, s),
+        _ => {
+            return Err(anyhow!(
+                "malleability_cases.json: the honest signature does not decode"
+            ))
+        }
+    };
+    let s_reduced = Scalar::from_bytes_mod_order(s.try_into().unwrap());
+    check_all(
+        "malleability_cases.json",
+        vectors,
+        |_, tv| tv.comment.clone(),
+        |idx, tv| {
+            let mut mismatches = Vec::new();
+            if (tv.message, tv.pub_key) != (honest.message, honest.pub_key) {
+                mismatches.push("it is not for the honest message and public key".to_string());
+            }
+            if tv.signature.len() != 64 {
+                mismatches.push(format!("the signature is {} bytes", tv.signature.len()));
+                return mismatches;
+            }
+            let (r_bytes, s_bytes) = tv.signature.split_at(32);
+            let r_prime = match deserialize_point(r_bytes) {
+                Ok(r_prime) => r_prime,
+                Err(_) => return vec!["R does not decode".to_string()],
+            };
+            let same_s = s_bytes == s;
+            let r_plus_torsion = PointClass::of(&(r_prime - r)) == Small;
+            let (derived, producer) = match tv.variant {
+                Variant::Honest => (idx == 0, Producer::Signer),
+                Variant::SPlusMultipleOfL => (
+                    r_bytes == &honest.signature[..32]
+                        && !same_s
+                        && Scalar::from_bytes_mod_order(s_bytes.try_into().unwrap()) == s_reduced,
+                    Producer::ThirdParty,
+                ),
+                Variant::RPlusTorsion => (same_s && r_plus_torsion, Producer::ThirdParty),
+                Variant::ReencodedR => (
+                    same_s && r_prime == r && r_bytes != &honest.signature[..32],
+                    Producer::ThirdParty,
+                ),
+                Variant::RPlusTorsionResigned => (r_plus_torsion, Producer::Signer),
+            };
+            if !derived {
+                mismatches.push(format!("it is not a {:?} variant", tv.variant));
+            }
+            if tv.producer != producer {
+                mismatches.push(format!(
+                    "it is produced by {:?}, not {:?}",
+                    tv.producer, producer
+                ));
+            }
+            match tv.variant {
+                Variant::Honest => mismatches.extend(check_accepts(
+                    &rfc_8032(),
+                    &tv.message,
+                    &tv.pub_key,
+                    &tv.signature,
+                )),
+                Variant::RPlusTorsionResigned => mismatches.extend(check_accepts(
+                    &policy::COFACTORED,
+                    &tv.message,
+                    &tv.pub_key,
+                    &tv.signature,
+                )),
+                _ => (),
+            }
+            mismatches
+        },
+    )
+}
+
+/// Every way `signature` of `message` under `pub_key` differs from the one
+/// re-derived from `seed`
+fn check_signed(
+    seed: &[u8; 32],
+    pub_key: &[u8; 32],
+    message: &[u8],
+    signature: &[u8],
+) -> Vec<String> {
+    let expanded = signing::expand_seed(seed);
+    let mut mismatches = Vec::new();
+    if pub_key != &expanded.public_key() {
+        mismatches.push("the public key is not derived from the seed".to_string());
+    }
+    if signature != &expanded.sign(message)[..] {
+        mismatches.push("the signature is not derived from the seed".to_string());
+    }
+    mismatches.extend(check_accepts(&rfc_8032(), message, pub_key, signature));
+    mismatches
+}
+
+/// Check that each key generation vector re-derives from its seed, both with
+/// and without clamping
+pub fn validate_keygen(vectors: &[KeygenVector]) -> Result<()> {
+    check_all(
+        "keygen_cases.json",
+        vectors,
+        |_, tv| tv.comment.to_string(),
+        |_, tv| {
+            let mut mismatches = check_signed(&tv.seed, &tv.pub_key, &tv.message, &tv.signature);
+            if tv.expanded_key[..] != Sha512::digest(&tv.seed)[..] {
+                mismatches.push("the expanded key is not SHA-512(seed)".to_string());
+            }
+            let raw_scalar: [u8; 32] = tv.expanded_key[..32].try_into().unwrap();
+            if tv.scalar != signing::clamp(&raw_scalar) {
+                mismatches.push("the scalar is not the clamped raw scalar".to_string());
+            }
+
+            let unclamped = Scalar::from_bytes_mod_order(raw_scalar);
+            if tv.unclamped_pub_key != (unclamped * ED25519_BASEPOINT_POINT).compress().to_bytes() {
+                mismatches
+                    .push("the unclamped public key is not derived from the seed".to_string());
+            }
+            if tv.unclamped_signature
+                != signing::expand_seed(&tv.seed).sign_with_scalar(&unclamped, &tv.message)
+            {
+                mismatches.push("the unclamped signature is not derived from the seed".to_string());
+            }
+            mismatches.extend(
+                check_accepts(
+                    &rfc_8032(),
+                    &tv.message,
+                    &tv.unclamped_pub_key,
+                    &tv.unclamped_signature,
+                )
+                .into_iter()
+                .map(|m| format!("unclamped: {}", m)),
+            );
+            if (tv.scalar == raw_scalar) != (tv.pub_key == tv.unclamped_pub_key) {
+                mismatches.push("clamping does not change exactly the public key".to_string());
+            }
+            mismatches
+        },
+    )
+}
+
+/// Check that each signing vector re-derives from its seed
+pub fn validate_signing(vectors: &[SigningVector]) -> Result<()> {
+    check_all(
+        "signing_cases.json",
+        vectors,
+        |_, tv| tv.comment.to_string(),
+        |_, tv| check_signed(&tv.seed, &tv.pub_key, &tv.message, &tv.signature),
+    )
+}
+
+/// Check that each key substitution vector re-derives from its seed, and that
+/// its two signatures reveal the secret scalar
+pub fn validate_key_substitution(vectors: &[KeySubstitutionVector]) -> Result<()> {
+    check_all(
+        "key_substitution_cases.json",
+        vectors,
+        |_, tv| tv.comment.to_string(),
+        |_, tv| {
+            let mut mismatches = check_signed(&tv.seed, &tv.pub_key, &tv.message, &tv.signature);
+            let expanded = signing::expand_seed(&tv.seed);
+            if tv.oracle_signature
+                != expanded.sign_with_public_key(&tv.supplied_pub_key, &tv.message)
+            {
+                mismatches.push("the oracle signature is not derived from the seed".to_string());
+            }
+            match signing::recover_from_double_public_key(
+                &tv.message,
+                &tv.pub_key,
+                &tv.signature,
+                &tv.supplied_pub_key,
+                &tv.oracle_signature,
+            ) {
+                Ok(a) if a == expanded.scalar().reduce() => (),
+                Ok(_) => mismatches.push("the signatures reveal the wrong scalar".to_string()),
+                Err(e) => {
+                    mismatches.push(format!("the signatures do not reveal the scalar: {}", e))
+                }
+            }
+            mismatches
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_test_vectors, grid, malleability, ownership, repudiation};
+
+    #[test]
+    fn test_validate() {
+        let vec = generate_test_vectors();
+        validate(&vec).unwrap();
+
+        // a wrong S, and a wrong R
+        let mut changed = vec.clone();
+        changed[6].signature[63] = 0;
+        changed[3].signature[..32].copy_from_slice(&crate::EIGHT_TORSION[2]);
+        let err = validate(&changed).unwrap_err().to_string();
+        assert!(
+            err.contains("vector #6 (large_s): 0 < S < L, declared S ≥ L"),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains("vector #3 (non_zero_mixed_mixed): R is small, declared mixed"),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains("vector #3 (non_zero_mixed_mixed): cofactored rejects it"),
+            "{}",
+            err
+        );
+        assert!(validate(&vec[1..]).is_err());
+    }
+
+    fn assert_fails(result: Result<()>, expected: &str) {
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains(expected), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_files() {
+        let mut grid = grid::grid_vectors();
+        validate_grid(&grid).unwrap();
+        grid[0].a_class = Prime;
+        assert_fails(validate_grid(&grid), "A is identity, declared prime-order");
+        assert_fails(
+            validate_grid(&grid[1..]),
+            "47 vectors generated, for 48 cells",
+        );
+
+        let mut ownership = ownership::ownership_vectors();
+        validate_ownership(&ownership).unwrap();
+        ownership[1].policy = "strict";
+        assert_fails(
+            validate_ownership(&ownership),
+            "pub_key1: strict rejects it",
+        );
+
+        let mut repudiation = repudiation::repudiation_vectors();
+        validate_repudiation(&repudiation).unwrap();
+        repudiation[0].torsion_order = 8;
+        // order 2, cofactorless: the cofactorless equation holds
+        repudiation[8].policy = "cofactored";
+        assert_fails(validate_repudiation(&repudiation), "A is of order 1");
+        assert_fails(
+            validate_repudiation(&repudiation),
+            "vector #8 (order 2, cofactored): message1: cofactorless accepts it",
+        );
+
+        let mut malleability = malleability::malleability_vectors();
+        validate_malleability(&malleability).unwrap();
+        malleability[1].variant = Variant::RPlusTorsion;
+        assert_fails(
+            validate_malleability(&malleability),
+            "vector #1 (S + 1L): it is not a RPlusTorsion variant",
+        );
+        assert_fails(
+            validate_malleability(&malleability[1..]),
+            "the first vector is not the honest signature",
+        );
+
+        let mut keygen = signing::keygen_clamping_vectors();
+        validate_keygen(&keygen).unwrap();
+        keygen[1].unclamped_signature = keygen[1].signature.clone();
+        assert_fails(
+            validate_keygen(&keygen),
+            "the unclamped signature is not derived from the seed",
+        );
+
+        let mut signing_vec = signing::signing_vectors();
+        validate_signing(&signing_vec).unwrap();
+        signing_vec[0].signature[0] ^= 1;
+        assert_fails(
+            validate_signing(&signing_vec),
+            "vector #0 (TEST 1): the signature is not derived from the seed",
+        );
+
+        let mut substitution = signing::key_substitution_vectors();
+        validate_key_substitution(&substitution).unwrap();
+        substitution[0].oracle_signature = substitution[0].signature.clone();
+        assert_fails(
+            validate_key_substitution(&substitution),
+            "vector #0 (unrelated public key): the signatures reveal the wrong scalar",
+        );
+    }
+}